@@ -0,0 +1,91 @@
+use std::cmp::Ordering;
+
+use rand::{thread_rng, Rng};
+
+use crate::{mode::Difficulty, na, side::Side, Ball};
+
+// Computer controlled paddle
+pub struct Ai {
+    difficulty: Difficulty,
+    // Where on the paddle the computer aims for, picked again for every return
+    aim_offset: f32,
+    // Length of the edge the paddle moves along
+    track_length: f32,
+}
+
+impl Ai {
+    pub fn new(difficulty: Difficulty, track_length: f32) -> Self {
+        Self {
+            difficulty,
+            aim_offset: 0.,
+            track_length,
+        }
+    }
+
+    // Fraction of the paddle speed the computer may use
+    pub fn speed_factor(&self) -> f32 {
+        match self.difficulty {
+            Difficulty::Easy => 0.45,
+            Difficulty::Normal => 0.7,
+            Difficulty::Hard => 1.,
+        }
+    }
+
+    // Largest distance in pixels between the aim and the ball
+    fn aim_error(&self) -> f32 {
+        match self.difficulty {
+            Difficulty::Easy => 45.,
+            Difficulty::Normal => 30.,
+            Difficulty::Hard => 15.,
+        }
+    }
+
+    // Where along its edge the paddle at `paddle` should move to, going for the
+    // ball that arrives first
+    pub fn target(&mut self, side: Side, paddle: na::Point2<f32>, balls: &[Ball]) -> f32 {
+        let depth = side.depth(paddle.coords);
+        let ball = match balls
+            .iter()
+            .filter(|ball| {
+                let time = Self::time_to_reach(side, depth, ball);
+                time > 0. && time.is_finite()
+            })
+            .min_by(|a, b| {
+                Self::time_to_reach(side, depth, a)
+                    .partial_cmp(&Self::time_to_reach(side, depth, b))
+                    .unwrap_or(Ordering::Equal)
+            }) {
+            Some(ball) => ball,
+            None => {
+                let error = self.aim_error();
+                self.aim_offset = thread_rng().gen_range(-error, error);
+                return self.track_length * 0.5;
+            }
+        };
+
+        let target = match self.difficulty {
+            Difficulty::Easy => side.along(ball.position.coords),
+            _ => self.predict(side, depth, ball),
+        };
+        target + self.aim_offset
+    }
+
+    // Seconds until the ball reaches the paddle at `depth`, negative or infinite if
+    // it never will
+    fn time_to_reach(side: Side, depth: f32, ball: &Ball) -> f32 {
+        (depth - side.depth(ball.position.coords)) / side.depth(ball.velocity)
+    }
+
+    // Follow the ball to the paddle, folding the path at both ends of the track
+    fn predict(&self, side: Side, depth: f32, ball: &Ball) -> f32 {
+        let length = self.track_length;
+        let time = Self::time_to_reach(side, depth, ball);
+        let along = side.along(ball.position.coords) + side.along(ball.velocity) * time;
+        let along = along.rem_euclid(length * 2.);
+        if along > length {
+            length * 2. - along
+        } else {
+            along
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collidable::Collidable,
+    filesystem, graphics, na,
+    player::PADDLE_WIDTH_HALF,
+    side::Side,
+    storage::Storage,
+    viewport::{ARENA_HEIGHT, ARENA_WIDTH},
+    world::PADDING,
+    Context, DrawParam, GameResult,
+};
+
+// Built-in arenas ship in resources/arenas, the ones saved by the editor end up
+// in the user config directory. Both are found under this path.
+pub const ARENAS_DIR: &str = "/arenas";
+// Anything smaller leaves no room to play
+const MIN_SIZE: f32 = 200.;
+const MAX_SIZE: f32 = 2000.;
+// Thickness of the end walls drawn either side of a goal opening
+const GOAL_POST_THICKNESS: f32 = 6.;
+
+// A rectangle the ball bounces off, moving ones slide back and forth
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Obstacle {
+    // Centre of the obstacle, where a moving obstacle starts
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // The other end of a moving obstacle's path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moves_to: Option<[f32; 2]>,
+    // Pixels per second along the path
+    #[serde(default)]
+    pub speed: f32,
+
+    // How far along the path it is, from 0.0 at the start to 1.0 at the end
+    #[serde(skip)]
+    progress: f32,
+    #[serde(skip)]
+    returning: bool,
+}
+
+impl Obstacle {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            moves_to: None,
+            speed: 0.,
+            progress: 0.,
+            returning: false,
+        }
+    }
+
+    pub fn position(&self) -> na::Point2<f32> {
+        let start = na::Point2::new(self.x, self.y);
+        match self.moves_to {
+            Some([x, y]) => start + (na::Point2::new(x, y) - start) * self.progress,
+            None => start,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let length = match self.moves_to {
+            Some([x, y]) => (na::Point2::new(x, y) - na::Point2::new(self.x, self.y)).norm(),
+            None => return,
+        };
+        if length <= 0. || self.speed <= 0. {
+            return;
+        }
+
+        let step = self.speed * dt / length;
+        if self.returning {
+            self.progress -= step;
+        } else {
+            self.progress += step;
+        }
+
+        // Turn around at either end
+        if self.progress >= 1. {
+            self.progress = 1.;
+            self.returning = true;
+        } else if self.progress <= 0. {
+            self.progress = 0.;
+            self.returning = false;
+        }
+    }
+
+    // Back to the start of the path for a new match
+    pub fn reset(&mut self) {
+        self.progress = 0.;
+        self.returning = false;
+    }
+}
+
+impl Collidable for Obstacle {
+    fn get_position(&self) -> na::Point2<f32> {
+        self.position()
+    }
+
+    fn get_size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+}
+
+// Layout of the playing field, loaded from a TOML or JSON file
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Arena {
+    pub name: String,
+    pub width: f32,
+    pub height: f32,
+    // Height of the goal opening in the left and right end walls, centred
+    // vertically. Without it both end walls are goals from top to bottom.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal_heights: Option<[f32; 2]>,
+    // Centre of the left and right paddle at the start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paddle_starts: Option<[[f32; 2]; 2]>,
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+}
+
+impl Arena {
+    // The original empty field
+    pub fn classic() -> Self {
+        Self {
+            name: String::from("Classic"),
+            width: ARENA_WIDTH,
+            height: ARENA_HEIGHT,
+            goal_heights: None,
+            paddle_starts: None,
+            obstacles: Vec::new(),
+        }
+    }
+
+    // The classic arena followed by every arena file found, sorted by file name
+    pub fn load_all(ctx: &mut Context) -> Vec<Arena> {
+        let mut paths: Vec<_> = filesystem::read_dir(ctx, ARENAS_DIR)
+            .map(|paths| paths.collect())
+            .unwrap_or_default();
+        paths.sort();
+        paths.dedup();
+
+        let mut arenas = vec![Self::classic()];
+        for path in paths {
+            let path = path.to_string_lossy().replace('\\', "/");
+            match Storage::read::<Arena>(ctx, &path) {
+                Ok(arena) if arena.is_valid() => arenas.push(arena),
+                Ok(_) => eprintln!("Skipping {}: arena layout out of range", path),
+                Err(error) => eprintln!("Could not load arena {}: {}", path, error),
+            }
+        }
+        arenas
+    }
+
+    // Saved as TOML to the user config directory, named after the arena
+    pub fn save(&self, ctx: &mut Context) -> GameResult<String> {
+        let file_name: String = self
+            .name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = format!("{}/{}.toml", ARENAS_DIR, file_name);
+
+        filesystem::create_dir(ctx, ARENAS_DIR)?;
+        Storage::save(ctx, &path, self)?;
+        Ok(path)
+    }
+
+    // Sizes in range and everything else inside the arena, NaN fails every check
+    fn is_valid(&self) -> bool {
+        let in_range = |size: f32| (MIN_SIZE..=MAX_SIZE).contains(&size);
+        if !in_range(self.width) || !in_range(self.height) {
+            return false;
+        }
+        let inside =
+            |[x, y]: [f32; 2]| (0.0..=self.width).contains(&x) && (0.0..=self.height).contains(&y);
+
+        let goals_valid = self.goal_heights.is_none_or(|heights| {
+            heights
+                .iter()
+                .all(|height| *height > 0. && *height <= self.height)
+        });
+        let paddles_valid = self
+            .paddle_starts
+            .is_none_or(|starts| starts.iter().copied().all(inside));
+        let obstacles_valid = self.obstacles.iter().all(|obstacle| {
+            obstacle.width > 0.
+                && obstacle.width <= self.width
+                && obstacle.height > 0.
+                && obstacle.height <= self.height
+                && obstacle.speed >= 0.
+                && obstacle.speed.is_finite()
+                && inside([obstacle.x, obstacle.y])
+                && obstacle.moves_to.is_none_or(inside)
+        });
+        goals_valid && paddles_valid && obstacles_valid
+    }
+
+    // Goals of the given height on both end edges, unless the arena has its own
+    pub fn add_default_goals(&mut self, height: f32) {
+        if self.goal_heights.is_none() {
+            self.goal_heights = Some([height, height]);
+        }
+    }
+
+    pub fn center(&self) -> na::Point2<f32> {
+        na::Point2::new(self.width * 0.5, self.height * 0.5)
+    }
+
+    // Start and end of the goal opening along the given edge, goals on the top
+    // and bottom edge are as wide as the arena
+    pub fn goal_range(&self, side: Side) -> (f32, f32) {
+        let index = match side {
+            Side::Left => 0,
+            Side::Right => 1,
+            Side::Top | Side::Bottom => return (0., self.width),
+        };
+        let goal_height = self
+            .goal_heights
+            .map_or(self.height, |heights| heights[index].min(self.height));
+        let top = (self.height - goal_height) * 0.5;
+        (top, top + goal_height)
+    }
+
+    // Paddles on the top and bottom edge always start in the middle of it
+    pub fn paddle_start(&self, side: Side) -> na::Point2<f32> {
+        let offset = PADDLE_WIDTH_HALF + PADDING;
+        match (side, self.paddle_starts) {
+            (Side::Left, Some(starts)) => na::Point2::new(starts[0][0], starts[0][1]),
+            (Side::Right, Some(starts)) => na::Point2::new(starts[1][0], starts[1][1]),
+            (Side::Left, None) => na::Point2::new(offset, self.height * 0.5),
+            (Side::Right, None) => na::Point2::new(self.width - offset, self.height * 0.5),
+            (Side::Top, _) => na::Point2::new(self.width * 0.5, offset),
+            (Side::Bottom, _) => na::Point2::new(self.width * 0.5, self.height - offset),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.obstacles.iter_mut().for_each(Obstacle::reset);
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for obstacle in &mut self.obstacles {
+            obstacle.update(dt);
+        }
+    }
+
+    // Obstacles and the end walls above and below each goal opening
+    pub fn draw(&self, ctx: &mut Context) {
+        let color = graphics::Color::from_rgba(128, 128, 128, 255);
+        let mut rects: Vec<graphics::Rect> = self
+            .obstacles
+            .iter()
+            .map(|obstacle| {
+                let position = obstacle.position();
+                graphics::Rect::new(
+                    position.x - obstacle.width * 0.5,
+                    position.y - obstacle.height * 0.5,
+                    obstacle.width,
+                    obstacle.height,
+                )
+            })
+            .collect();
+
+        for (side, x) in [
+            (Side::Left, 0.),
+            (Side::Right, self.width - GOAL_POST_THICKNESS),
+        ]
+        .iter()
+        {
+            let (top, bottom) = self.goal_range(*side);
+            for (y, height) in [(0., top), (bottom, self.height - bottom)].iter() {
+                if *height > 0. {
+                    rects.push(graphics::Rect::new(*x, *y, GOAL_POST_THICKNESS, *height));
+                }
+            }
+        }
+
+        if rects.is_empty() {
+            return;
+        }
+        let mut builder = graphics::MeshBuilder::new();
+        for rect in rects {
+            builder.rectangle(graphics::DrawMode::fill(), rect, color);
+        }
+        let mesh = builder.build(ctx).unwrap();
+        graphics::draw(ctx, &mesh, DrawParam::default()).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Arena {
+        toml::from_str(source).unwrap()
+    }
+
+    const PILLARS: &str = r#"
+        name = "Pillars"
+        width = 800.0
+        height = 600.0
+        goal_heights = [200.0, 300.0]
+        paddle_starts = [[20.0, 300.0], [780.0, 300.0]]
+
+        [[obstacles]]
+        x = 400.0
+        y = 150.0
+        width = 40.0
+        height = 80.0
+
+        [[obstacles]]
+        x = 400.0
+        y = 450.0
+        width = 40.0
+        height = 80.0
+        moves_to = [400.0, 300.0]
+        speed = 60.0
+    "#;
+
+    #[test]
+    fn parses_optional_fields() {
+        let arena = parse(PILLARS);
+        assert!(arena.is_valid());
+        assert_eq!(arena.goal_range(Side::Left), (200., 400.));
+        assert_eq!(arena.goal_range(Side::Right), (150., 450.));
+        assert_eq!(arena.paddle_start(Side::Right), na::Point2::new(780., 300.));
+        assert_eq!(arena.obstacles.len(), 2);
+        assert_eq!(arena.obstacles[0].moves_to, None);
+        assert_eq!(arena.obstacles[1].moves_to, Some([400., 300.]));
+
+        let bare = parse("name = \"Bare\"\nwidth = 800.0\nheight = 600.0");
+        assert!(bare.is_valid());
+        assert_eq!(bare.goal_range(Side::Left), (0., 600.));
+        assert!(bare.obstacles.is_empty());
+    }
+
+    #[test]
+    fn saved_arenas_parse_back() {
+        let arena = parse(PILLARS);
+        let source = toml::to_string(&arena).unwrap();
+        assert!(parse(&source) == arena);
+    }
+
+    #[test]
+    fn rejects_out_of_range_sizes() {
+        for (width, height) in [(100., 600.), (800., 3000.), (f32::NAN, 600.)].iter() {
+            let mut arena = Arena::classic();
+            arena.width = *width;
+            arena.height = *height;
+            assert!(!arena.is_valid());
+        }
+    }
+
+    #[test]
+    fn rejects_layouts_outside_the_arena() {
+        let breakages: [fn(&mut Arena); 9] = [
+            |arena| arena.goal_heights = Some([0., 200.]),
+            |arena| arena.goal_heights = Some([200., 700.]),
+            |arena| arena.paddle_starts = Some([[20., 300.], [900., 300.]]),
+            |arena| arena.paddle_starts = Some([[f32::NAN, 300.], [780., 300.]]),
+            |arena| arena.obstacles[0].width = -40.,
+            |arena| arena.obstacles[0].height = 5000.,
+            |arena| arena.obstacles[0].x = f32::NAN,
+            |arena| arena.obstacles[1].moves_to = Some([400., -10.]),
+            |arena| arena.obstacles[1].speed = f32::INFINITY,
+        ];
+        for breakage in breakages.iter() {
+            let mut arena = parse(PILLARS);
+            breakage(&mut arena);
+            assert!(!arena.is_valid());
+        }
+    }
+}
@@ -5,9 +5,27 @@ const BALL_SIZE: f32 = 30.0;
 const BALL_SIZE_HALF: f32 = BALL_SIZE * 0.5;
 const BALL_SPEED: f32 = 400.;
 
+// Puck data, the share of its speed a puck loses every second, its top speed and the
+// share of the approach speed it keeps when it bounces off a mallet
+const PUCK_FRICTION: f32 = 0.3;
+const PUCK_MAX_SPEED: f32 = 1100.;
+const PUCK_RESTITUTION: f32 = 0.9;
+
+// Sticky paddle: the ball rides along with the paddle until it's let go
+pub struct Hold {
+    pub player: usize,
+    offset: na::Vector2<f32>,
+    remaining: f32,
+}
+
 pub struct Ball {
     pub position: na::Point2<f32>,
     pub velocity: na::Vector2<f32>,
+    // Player whose paddle touched the ball last
+    pub last_hit: Option<usize>,
+    pub hold: Option<Hold>,
+    // Seconds left for an extra ball that vanishes again
+    lifetime: Option<f32>,
     mesh: graphics::Mesh,
 }
 
@@ -24,39 +42,239 @@ impl Ball {
         Self {
             position: na::Point2::new(x, y),
             velocity,
+            last_hit: None,
+            hold: None,
+            lifetime: None,
             mesh,
         }
     }
 
+    // A round ball for air hockey
+    pub fn puck(ctx: &mut Context, x: f32, y: f32) -> Self {
+        let mut puck = Self::new(ctx, x, y);
+        puck.mesh = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            na::Point2::new(0., 0.),
+            BALL_SIZE_HALF,
+            0.5,
+            graphics::WHITE,
+        )
+        .unwrap();
+        puck
+    }
+
+    pub fn with_lifetime(mut self, seconds: f32) -> Self {
+        self.lifetime = Some(seconds);
+        self
+    }
+
+    pub fn age(&mut self, dt: f32) {
+        if let Some(lifetime) = &mut self.lifetime {
+            *lifetime -= dt;
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        matches!(self.lifetime, Some(lifetime) if lifetime <= 0.)
+    }
+
     pub fn get_mesh(&self) -> &graphics::Mesh {
         &self.mesh
     }
 
-    pub fn reset(&mut self, ctx: &mut Context) {
-        let (scr_width, scr_height) = graphics::drawable_size(ctx);
-        self.position.x = scr_width * 0.5;
-        self.position.y = scr_height * 0.5;
+    // Back to `center` for a serve in a random direction
+    pub fn reset(&mut self, center: na::Point2<f32>) {
+        self.position = center;
         Util::randomize_vec(&mut self.velocity, BALL_SPEED, BALL_SPEED);
+        self.last_hit = None;
+        self.hold = None;
+        self.lifetime = None;
     }
 
-    pub fn update(&mut self, ctx: &mut Context, dt: f32) {
+    pub fn update(&mut self, dt: f32) {
         self.position += self.velocity * dt;
+    }
+
+    // Returns true if the ball bounced off the obstacle, it's pushed out along
+    // the axis it overlaps the least and heads away on that axis
+    pub fn bounce_off(&mut self, obstacle: &dyn Collidable) -> bool {
+        let overlap = match self.overlap(obstacle) {
+            Some(overlap) => overlap,
+            None => return false,
+        };
+
+        let away = self.position - obstacle.get_position();
+        if overlap.x < overlap.y {
+            let direction = if away.x < 0. { -1. } else { 1. };
+            self.position.x += overlap.x * direction;
+            self.velocity.x = self.velocity.x.abs() * direction;
+        } else {
+            let direction = if away.y < 0. { -1. } else { 1. };
+            self.position.y += overlap.y * direction;
+            self.velocity.y = self.velocity.y.abs() * direction;
+        }
+        true
+    }
 
-        let scr_height = graphics::drawable_size(ctx).1;
+    // Returns true if the ball bounced off one of the walls around it, `min` holds
+    // the left and top wall faces and `max` the right and bottom ones. Any of them
+    // can be infinite when there's no wall.
+    pub fn bounce_off_walls(&mut self, min: na::Point2<f32>, max: na::Point2<f32>) -> bool {
+        let bounced_x = Self::bounce(
+            &mut self.position.x,
+            &mut self.velocity.x,
+            min.x + BALL_SIZE_HALF,
+            max.x - BALL_SIZE_HALF,
+        );
+        let bounced_y = Self::bounce(
+            &mut self.position.y,
+            &mut self.velocity.y,
+            min.y + BALL_SIZE_HALF,
+            max.y - BALL_SIZE_HALF,
+        );
+        bounced_x || bounced_y
+    }
 
-        // Ceiling bounce
-        if self.position.y < BALL_SIZE_HALF {
-            self.position.y = BALL_SIZE_HALF;
-            self.velocity.y = self.velocity.y.abs();
-        } else if self.position.y > scr_height - BALL_SIZE_HALF {
-            self.position.y = scr_height - BALL_SIZE_HALF;
-            self.velocity.y = -self.velocity.y.abs();
+    // Send the ball back the way `normal` points if it's heading against it,
+    // returns true if it was
+    pub fn deflect(&mut self, normal: na::Vector2<f32>) -> bool {
+        let approach = self.velocity.dot(&normal);
+        if approach >= 0. {
+            return false;
         }
+        self.velocity -= normal * approach * 2.;
+        true
     }
 
-    pub fn draw(&self, ctx: &mut Context) {
-        let mut draw_param = graphics::DrawParam::default();
-        draw_param.dest = self.position.into();
+    // Slow down like a puck on the table, never going faster than its top speed
+    pub fn slide(&mut self, dt: f32) {
+        self.velocity *= (1. - PUCK_FRICTION * dt).max(0.);
+        let speed = self.velocity.norm();
+        if speed > PUCK_MAX_SPEED {
+            self.velocity *= PUCK_MAX_SPEED / speed;
+        }
+    }
+
+    // Bounce off a round mallet at `center` moving with `velocity`, the mallet's
+    // speed carries over into the ball. Returns true if the mallet hit the ball.
+    pub fn strike(
+        &mut self,
+        center: na::Point2<f32>,
+        radius: f32,
+        velocity: na::Vector2<f32>,
+    ) -> bool {
+        let delta = self.position - center;
+        let distance = delta.norm();
+        let reach = radius + BALL_SIZE_HALF;
+        if distance >= reach {
+            return false;
+        }
+
+        // Push the ball out of the mallet, straight ahead if they're on top of each other
+        let normal = if distance > 0. {
+            delta / distance
+        } else {
+            na::Vector2::new(1., 0.)
+        };
+        self.position = center + normal * reach;
+
+        // Bounce off in the mallet's frame of reference
+        let approach = (self.velocity - velocity).dot(&normal);
+        if approach >= 0. {
+            return false;
+        }
+        self.velocity -= normal * approach * (1. + PUCK_RESTITUTION);
+        true
+    }
+
+    // Stick to the paddle at `paddle_position` for a while
+    pub fn catch(&mut self, player: usize, paddle_position: na::Point2<f32>, seconds: f32) {
+        self.hold = Some(Hold {
+            player,
+            offset: self.position - paddle_position,
+            remaining: seconds,
+        });
+    }
+
+    // Move along with the holding paddle, returns false once the ball is let go
+    pub fn follow(&mut self, paddle_position: na::Point2<f32>, dt: f32) -> bool {
+        let hold = match &mut self.hold {
+            Some(hold) => hold,
+            None => return false,
+        };
+
+        self.position = paddle_position + hold.offset;
+        hold.remaining -= dt;
+        if hold.remaining <= 0. {
+            self.hold = None;
+        }
+        true
+    }
+
+    // Keep one axis between `min` and `max`, reflecting the velocity at either end
+    fn bounce(position: &mut f32, velocity: &mut f32, min: f32, max: f32) -> bool {
+        if *position < min {
+            *position = min;
+            *velocity = velocity.abs();
+            true
+        } else if *position > max {
+            *position = max;
+            *velocity = -velocity.abs();
+            true
+        } else {
+            false
+        }
+    }
+
+    // Speed the ball up without changing its direction, up to `max_speed`
+    pub fn accelerate(&mut self, amount: f32, max_speed: f32) {
+        let speed = self.velocity.norm();
+        if speed > 0. {
+            self.velocity *= (speed + amount).min(max_speed) / speed;
+        }
+    }
+
+    // Bounce two balls off each other like equal mass circles, returns true on a hit
+    pub fn collide(&mut self, other: &mut Ball) -> bool {
+        Self::elastic(
+            (&mut self.position, &mut self.velocity),
+            (&mut other.position, &mut other.velocity),
+        )
+    }
+
+    // Two equal mass circles of the ball's size bouncing off each other, given
+    // as their position and velocity
+    fn elastic(
+        (position, velocity): (&mut na::Point2<f32>, &mut na::Vector2<f32>),
+        (other_position, other_velocity): (&mut na::Point2<f32>, &mut na::Vector2<f32>),
+    ) -> bool {
+        let delta = *other_position - *position;
+        let distance = delta.norm();
+        if distance >= BALL_SIZE || distance == 0. {
+            return false;
+        }
+
+        // Push them apart so they don't stick together
+        let normal = delta / distance;
+        let overlap = BALL_SIZE - distance;
+        *position -= normal * overlap * 0.5;
+        *other_position += normal * overlap * 0.5;
+
+        // Only balls moving towards each other swap their speeds along the normal
+        let approach = (*velocity - *other_velocity).dot(&normal);
+        if approach <= 0. {
+            return false;
+        }
+        *velocity -= normal * approach;
+        *other_velocity += normal * approach;
+        true
+    }
+
+    pub fn draw(&self, ctx: &mut Context, scale: na::Vector2<f32>) {
+        let draw_param = graphics::DrawParam::default()
+            .dest(self.position)
+            .scale(scale);
 
         graphics::draw(ctx, self.get_mesh(), draw_param).unwrap();
     }
@@ -71,3 +289,79 @@ impl Collidable for Ball {
         (BALL_SIZE, BALL_SIZE)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Body {
+        position: na::Point2<f32>,
+        velocity: na::Vector2<f32>,
+    }
+
+    impl Body {
+        fn new(x: f32, y: f32, vx: f32, vy: f32) -> Self {
+            Self {
+                position: na::Point2::new(x, y),
+                velocity: na::Vector2::new(vx, vy),
+            }
+        }
+    }
+
+    fn collide(a: &mut Body, b: &mut Body) -> bool {
+        Ball::elastic(
+            (&mut a.position, &mut a.velocity),
+            (&mut b.position, &mut b.velocity),
+        )
+    }
+
+    fn assert_close(a: na::Vector2<f32>, b: na::Vector2<f32>) {
+        assert!((a - b).norm() < 1e-3, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn head_on_balls_swap_velocities() {
+        let mut a = Body::new(0., 0., 100., 0.);
+        let mut b = Body::new(20., 0., -50., 0.);
+        assert!(collide(&mut a, &mut b));
+        assert_close(a.velocity, na::Vector2::new(-50., 0.));
+        assert_close(b.velocity, na::Vector2::new(100., 0.));
+        // Pushed apart to just touching
+        assert!(((b.position - a.position).norm() - BALL_SIZE).abs() < 1e-3);
+    }
+
+    #[test]
+    fn glancing_hits_keep_momentum_and_energy() {
+        let mut a = Body::new(0., 0., 300., 40.);
+        let mut b = Body::new(20., 12., -10., 0.);
+        let momentum = a.velocity + b.velocity;
+        let energy = a.velocity.norm_squared() + b.velocity.norm_squared();
+        assert!(collide(&mut a, &mut b));
+        assert_close(a.velocity + b.velocity, momentum);
+        let after = a.velocity.norm_squared() + b.velocity.norm_squared();
+        assert!((after - energy).abs() / energy < 1e-4);
+    }
+
+    #[test]
+    fn separating_balls_only_get_pushed_apart() {
+        let mut a = Body::new(0., 0., -100., 0.);
+        let mut b = Body::new(20., 0., 100., 0.);
+        assert!(!collide(&mut a, &mut b));
+        assert_close(a.velocity, na::Vector2::new(-100., 0.));
+        assert_close(b.velocity, na::Vector2::new(100., 0.));
+        assert!(((b.position - a.position).norm() - BALL_SIZE).abs() < 1e-3);
+    }
+
+    #[test]
+    fn far_apart_or_stacked_balls_are_left_alone() {
+        let mut a = Body::new(0., 0., 100., 0.);
+        let mut b = Body::new(BALL_SIZE, 0., -100., 0.);
+        assert!(!collide(&mut a, &mut b));
+        assert_eq!(b.position, na::Point2::new(BALL_SIZE, 0.));
+
+        let mut c = Body::new(5., 5., 100., 0.);
+        let mut d = Body::new(5., 5., -100., 0.);
+        assert!(!collide(&mut c, &mut d));
+        assert_eq!(c.position, d.position);
+    }
+}
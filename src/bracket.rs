@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{storage::Storage, Context};
+
+const TOURNAMENT_PATH: &str = "/tournament.toml";
+pub const MIN_ENTRANTS: usize = 2;
+pub const MAX_ENTRANTS: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Elimination {
+    #[default]
+    Single,
+    Double,
+}
+
+impl Elimination {
+    pub const ALL: [Elimination; 2] = [Elimination::Single, Elimination::Double];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Elimination::Single => "Single elimination",
+            Elimination::Double => "Double elimination",
+        }
+    }
+
+    // Losses that knock an entrant out
+    fn max_losses(self) -> u32 {
+        match self {
+            Elimination::Single => 1,
+            Elimination::Double => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Bracket {
+    Winners,
+    Losers,
+    // The last two entrants, whichever brackets they came from
+    Final,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TournamentMatch {
+    pub round: usize,
+    pub bracket: Bracket,
+    // Entrant indices, a match without `away` is a bye
+    pub home: usize,
+    pub away: Option<usize>,
+    pub winner: Option<usize>,
+    pub score: Option<[usize; 2]>,
+}
+
+// A knockout tournament between local profiles, played one match at a time
+//
+// Entrants come in seed order, best first. They're grouped by their number of
+// losses every round and paired best against worst within each group, so in
+// double elimination the winners and losers brackets run side by side until
+// one entrant is left in each and they meet in the final. A loss for the
+// winners bracket finalist sends both to the losers bracket, which plays out
+// as the bracket reset.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Tournament {
+    pub entrants: Vec<String>,
+    pub elimination: Elimination,
+    pub losses: Vec<u32>,
+    // Byes each entrant has had so far
+    #[serde(default)]
+    pub byes: Vec<u32>,
+    pub round: usize,
+    pub matches: Vec<TournamentMatch>,
+}
+
+impl Tournament {
+    pub fn new(entrants: Vec<String>, elimination: Elimination) -> Self {
+        let mut tournament = Self {
+            losses: vec![0; entrants.len()],
+            byes: vec![0; entrants.len()],
+            entrants,
+            elimination,
+            round: 0,
+            matches: Vec::new(),
+        };
+        tournament.start_round();
+        tournament
+    }
+
+    // A tournament in progress, if one was saved and isn't over yet
+    pub fn load(ctx: &mut Context) -> Option<Self> {
+        let mut tournament: Self = Storage::load(ctx, TOURNAMENT_PATH);
+        tournament.byes.resize(tournament.entrants.len(), 0);
+        if tournament.entrants.len() >= MIN_ENTRANTS
+            && tournament.losses.len() == tournament.entrants.len()
+            && tournament.champion().is_none()
+        {
+            Some(tournament)
+        } else {
+            None
+        }
+    }
+
+    pub fn save(&self, ctx: &mut Context) {
+        if let Err(error) = Storage::save(ctx, TOURNAMENT_PATH, self) {
+            eprintln!("Could not save tournament: {}", error);
+        }
+    }
+
+    // Forget the saved tournament once it's over
+    pub fn remove(ctx: &mut Context) {
+        Storage::delete(ctx, TOURNAMENT_PATH);
+    }
+
+    fn is_alive(&self, entrant: usize) -> bool {
+        self.losses[entrant] < self.elimination.max_losses()
+    }
+
+    fn alive(&self) -> Vec<usize> {
+        (0..self.entrants.len())
+            .filter(|entrant| self.is_alive(*entrant))
+            .collect()
+    }
+
+    pub fn champion(&self) -> Option<usize> {
+        match self.alive().as_slice() {
+            [champion] => Some(*champion),
+            _ => None,
+        }
+    }
+
+    // Index of the next match to play
+    pub fn next_match(&self) -> Option<usize> {
+        self.matches.iter().position(|m| m.winner.is_none())
+    }
+
+    // Store the result of the next match, `home_won` is true if player 1 won
+    pub fn record(&mut self, home_won: bool, score: [usize; 2]) {
+        let index = match self.next_match() {
+            Some(index) => index,
+            None => return,
+        };
+
+        let played = &mut self.matches[index];
+        let (winner, loser) = match (played.away, home_won) {
+            (Some(away), true) => (played.home, away),
+            (Some(away), false) => (away, played.home),
+            (None, _) => return,
+        };
+        played.winner = Some(winner);
+        played.score = Some(score);
+        self.losses[loser] += 1;
+
+        if self.next_match().is_none() {
+            self.start_round();
+        }
+    }
+
+    // Pair up everyone still in the tournament for the next round
+    fn start_round(&mut self) {
+        let alive = self.alive();
+        if alive.len() < 2 {
+            return;
+        }
+
+        self.round += 1;
+
+        // The last two entrants always meet, whichever brackets they're in
+        if alive.len() == 2 {
+            self.push_match(Bracket::Final, alive[0], Some(alive[1]));
+            return;
+        }
+
+        for losses in 0..self.elimination.max_losses() {
+            let mut group: Vec<usize> = alive
+                .iter()
+                .copied()
+                .filter(|entrant| self.losses[*entrant] == losses)
+                .collect();
+
+            let bracket = if losses == 0 {
+                Bracket::Winners
+            } else {
+                Bracket::Losers
+            };
+
+            // An odd one out gets a bye, the best seed among those with the fewest
+            // byes so far, so it goes round everyone before anyone gets a second
+            if group.len() % 2 == 1 {
+                let bye = group
+                    .iter()
+                    .copied()
+                    .min_by_key(|entrant| (self.byes[*entrant], *entrant))
+                    .unwrap_or(group[0]);
+                group.retain(|entrant| *entrant != bye);
+                self.byes[bye] += 1;
+                self.push_match(bracket, bye, None);
+            }
+
+            let half = group.len() / 2;
+            for (home, away) in group.iter().zip(group.iter().rev()).take(half) {
+                self.push_match(bracket, *home, Some(*away));
+            }
+        }
+    }
+
+    fn push_match(&mut self, bracket: Bracket, home: usize, away: Option<usize>) {
+        self.matches.push(TournamentMatch {
+            round: self.round,
+            bracket,
+            home,
+            away,
+            // A bye goes straight through
+            winner: if away.is_none() { Some(home) } else { None },
+            score: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tournament(count: usize, elimination: Elimination) -> Tournament {
+        let entrants = (1..=count).map(|seed| format!("Seed {}", seed)).collect();
+        Tournament::new(entrants, elimination)
+    }
+
+    fn round(tournament: &Tournament, round: usize) -> Vec<(usize, Option<usize>)> {
+        tournament
+            .matches
+            .iter()
+            .filter(|m| m.round == round)
+            .map(|m| (m.home, m.away))
+            .collect()
+    }
+
+    // Play every match, `home_wins` decides each one, and check the pairings on the way
+    fn play_out<F: Fn(&TournamentMatch) -> bool>(tournament: &mut Tournament, home_wins: F) {
+        let mut played = 0;
+        while let Some(index) = tournament.next_match() {
+            let next = tournament.matches[index].clone();
+            let away = next.away.expect("byes are never left to play");
+            let losses = (tournament.losses[next.home], tournament.losses[away]);
+            match next.bracket {
+                Bracket::Winners => assert_eq!(losses, (0, 0)),
+                Bracket::Losers => assert_eq!(losses.0, losses.1),
+                Bracket::Final => assert_eq!(tournament.alive().len(), 2),
+            }
+
+            tournament.record(home_wins(&next), [1, 0]);
+            played += 1;
+            assert!(played < 100, "the tournament never ends");
+        }
+    }
+
+    #[test]
+    fn seeds_pair_best_against_worst() {
+        let tournament = tournament(8, Elimination::Single);
+        assert_eq!(
+            round(&tournament, 1),
+            [(0, Some(7)), (1, Some(6)), (2, Some(5)), (3, Some(4))]
+        );
+    }
+
+    #[test]
+    fn byes_go_to_the_best_seed_then_rotate() {
+        let mut tournament = tournament(5, Elimination::Single);
+        assert_eq!(
+            round(&tournament, 1),
+            [(0, None), (1, Some(4)), (2, Some(3))]
+        );
+
+        tournament.record(true, [1, 0]);
+        tournament.record(true, [1, 0]);
+        // Seed 1 already had a bye, the next best seed gets this one
+        assert_eq!(round(&tournament, 2), [(1, None), (0, Some(2))]);
+    }
+
+    #[test]
+    fn double_elimination_final_is_reset_by_a_loss() {
+        let mut tournament = tournament(2, Elimination::Double);
+        tournament.record(false, [0, 1]);
+        assert_eq!(tournament.champion(), None);
+        assert_eq!(round(&tournament, 2), [(0, Some(1))]);
+        assert!(tournament.matches[1].bracket == Bracket::Final);
+
+        // Both are down to their last life now
+        tournament.record(true, [1, 0]);
+        assert_eq!(tournament.champion(), None);
+        tournament.record(true, [1, 0]);
+        assert_eq!(tournament.champion(), Some(0));
+    }
+
+    #[test]
+    fn brackets_play_through_to_a_champion() {
+        for count in [3, 5, 8].iter() {
+            for elimination in Elimination::ALL.iter() {
+                for upsets in 0..3 {
+                    let mut tournament = tournament(*count, *elimination);
+                    play_out(&mut tournament, |m| (m.round + m.home + upsets) % 3 != 0);
+
+                    let champion = tournament.champion().expect("a champion");
+                    let played = tournament.matches.iter().filter(|m| m.away.is_some());
+                    let losses: u32 = tournament.losses.iter().sum();
+                    assert_eq!(played.count() as u32, losses);
+                    for (entrant, lost) in tournament.losses.iter().enumerate() {
+                        if entrant != champion {
+                            assert_eq!(*lost, elimination.max_losses());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
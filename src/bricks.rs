@@ -0,0 +1,113 @@
+use crate::{arena::Arena, collidable::Collidable, graphics, na, Ball, Context, DrawParam};
+
+const BRICK_WIDTH: f32 = 24.;
+const BRICK_HEIGHT: f32 = 48.;
+const BRICK_COLUMNS: usize = 3;
+// Space left around every brick when drawn, they still touch for the ball
+const BRICK_INSET: f32 = 2.;
+// Brick colour by the hit points left, weakest first
+const BRICK_COLORS: [(u8, u8, u8); 3] = [(96, 160, 96), (192, 160, 64), (192, 96, 64)];
+
+#[derive(Clone, Copy)]
+pub struct Brick {
+    pub position: na::Point2<f32>,
+    pub hit_points: i32,
+    // Hit points it started with, also the points it's worth
+    pub max_hit_points: i32,
+}
+
+impl Collidable for Brick {
+    fn get_position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    fn get_size(&self) -> (f32, f32) {
+        (BRICK_WIDTH, BRICK_HEIGHT)
+    }
+}
+
+// Columns of bricks down the middle of the arena for breakout, the middle
+// column is the toughest
+#[derive(Default)]
+pub struct BrickWall {
+    bricks: Vec<Brick>,
+}
+
+impl BrickWall {
+    pub fn new(arena: &Arena) -> Self {
+        let rows = (arena.height / BRICK_HEIGHT).floor() as usize;
+        let top = (arena.height - rows as f32 * BRICK_HEIGHT) * 0.5 + BRICK_HEIGHT * 0.5;
+        let left = arena.width * 0.5 - (BRICK_COLUMNS - 1) as f32 * BRICK_WIDTH * 0.5;
+
+        let mut bricks = Vec::with_capacity(rows * BRICK_COLUMNS);
+        for column in 0..BRICK_COLUMNS {
+            for row in 0..rows {
+                let hit_points = if column == BRICK_COLUMNS / 2 {
+                    3
+                } else {
+                    1 + (row % 2) as i32
+                };
+                bricks.push(Brick {
+                    position: na::Point2::new(
+                        left + column as f32 * BRICK_WIDTH,
+                        top + row as f32 * BRICK_HEIGHT,
+                    ),
+                    hit_points,
+                    max_hit_points: hit_points,
+                });
+            }
+        }
+        Self { bricks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bricks.is_empty()
+    }
+
+    // Distance from the middle of the arena to either face of the wall
+    pub fn half_width(&self) -> f32 {
+        BRICK_COLUMNS as f32 * BRICK_WIDTH * 0.5
+    }
+
+    // Bounce the ball off the first brick it touches and knock a hit point off.
+    // Returns the brick if the ball bounced off one, it's gone from the wall when
+    // it has no hit points left.
+    pub fn hit(&mut self, ball: &mut Ball) -> Option<Brick> {
+        let index = self
+            .bricks
+            .iter()
+            .position(|brick| brick.check_collision(ball))?;
+        ball.bounce_off(&self.bricks[index]);
+
+        let brick = &mut self.bricks[index];
+        brick.hit_points -= 1;
+        if brick.hit_points <= 0 {
+            Some(self.bricks.remove(index))
+        } else {
+            Some(*brick)
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        if self.bricks.is_empty() {
+            return;
+        }
+        let mut builder = graphics::MeshBuilder::new();
+        for brick in &self.bricks {
+            let (r, g, b) = BRICK_COLORS[(brick.hit_points as usize).clamp(1, 3) - 1];
+            let rect = graphics::Rect::new(
+                brick.position.x - BRICK_WIDTH * 0.5 + BRICK_INSET,
+                brick.position.y - BRICK_HEIGHT * 0.5 + BRICK_INSET,
+                BRICK_WIDTH - BRICK_INSET * 2.,
+                BRICK_HEIGHT - BRICK_INSET * 2.,
+            );
+            builder.rectangle(
+                graphics::DrawMode::fill(),
+                rect,
+                graphics::Color::from_rgb(r, g, b),
+            );
+        }
+        let mesh = builder.build(ctx).unwrap();
+        graphics::draw(ctx, &mesh, DrawParam::default()).unwrap();
+    }
+}
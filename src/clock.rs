@@ -0,0 +1,35 @@
+// Countdown for timed matches, only ticks while the ball is in play
+pub struct MatchClock {
+    remaining: f32,
+    // Time ran out with the scores level, the next point wins
+    pub sudden_death: bool,
+}
+
+impl MatchClock {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            remaining: seconds,
+            sudden_death: false,
+        }
+    }
+
+    // Returns true on the tick the time runs out
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if self.remaining <= 0. {
+            return false;
+        }
+
+        self.remaining = (self.remaining - dt).max(0.);
+        self.remaining <= 0.
+    }
+
+    // e.g. "2:05"
+    pub fn describe(&self) -> String {
+        if self.sudden_death {
+            return String::from("Sudden death");
+        }
+
+        let seconds = self.remaining.ceil() as i32;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
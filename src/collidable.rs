@@ -1,18 +1,76 @@
 use crate::na;
 
+// Axis aligned rectangles, `get_position` is the centre
 pub trait Collidable {
     fn get_position(&self) -> na::Point2<f32>;
     fn get_size(&self) -> (f32, f32);
 
-    fn check_collision(&self, other: &dyn Collidable) -> bool {
-        let r1x = self.get_position().x;
-        let r1y = self.get_position().y;
+    // How far the two rectangles overlap along each axis, None if they don't touch
+    fn overlap(&self, other: &dyn Collidable) -> Option<na::Vector2<f32>> {
         let (r1w, r1h) = self.get_size();
+        let (r2w, r2h) = other.get_size();
+        let distance = other.get_position() - self.get_position();
+
+        let overlap_x = (r1w + r2w) * 0.5 - distance.x.abs();
+        let overlap_y = (r1h + r2h) * 0.5 - distance.y.abs();
+        if overlap_x >= 0. && overlap_y >= 0. {
+            Some(na::Vector2::new(overlap_x, overlap_y))
+        } else {
+            None
+        }
+    }
+
+    fn check_collision(&self, other: &dyn Collidable) -> bool {
+        self.overlap(other).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Block(f32, f32, f32, f32);
 
-        let r2x = other.get_position().x;
-        let r2y = other.get_position().y;
-        let (r2w, r2h) = self.get_size();
+    impl Collidable for Block {
+        fn get_position(&self) -> na::Point2<f32> {
+            na::Point2::new(self.0, self.1)
+        }
+
+        fn get_size(&self) -> (f32, f32) {
+            (self.2, self.3)
+        }
+    }
+
+    #[test]
+    fn overlap_along_each_axis() {
+        let block = Block(0., 0., 20., 10.);
+        let other = Block(15., -4., 20., 10.);
+        assert_eq!(block.overlap(&other), Some(na::Vector2::new(5., 6.)));
+        assert_eq!(other.overlap(&block), Some(na::Vector2::new(5., 6.)));
+    }
+
+    #[test]
+    fn touching_edges_collide() {
+        let block = Block(0., 0., 20., 10.);
+        assert_eq!(
+            block.overlap(&Block(20., 0., 20., 10.)),
+            Some(na::Vector2::new(0., 10.))
+        );
+        assert!(block.check_collision(&Block(0., 10., 20., 10.)));
+    }
+
+    #[test]
+    fn apart_on_either_axis_misses() {
+        let block = Block(0., 0., 20., 10.);
+        assert_eq!(block.overlap(&Block(21., 0., 20., 10.)), None);
+        assert_eq!(block.overlap(&Block(0., -11., 20., 10.)), None);
+        assert!(!block.check_collision(&Block(30., 30., 20., 10.)));
+    }
 
-        r1x + r1w >= r2x && r1x <= r2x + r2w && r1y + r1h >= r2y && r1y <= r2y + r2h
+    #[test]
+    fn contained_rectangles_overlap_fully() {
+        let block = Block(0., 0., 40., 40.);
+        let inner = Block(0., 0., 10., 10.);
+        assert_eq!(block.overlap(&inner), Some(na::Vector2::new(25., 25.)));
     }
 }
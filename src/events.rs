@@ -0,0 +1,59 @@
+use crate::{na, powerups::PowerUpKind, settings::Settings, Context};
+
+// Things that happened in the simulation during a tick
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameEvent {
+    // `offset` is where the ball hit, from -1.0 (top or left) to 1.0 (bottom or
+    // right) of the paddle
+    BallHitPaddle {
+        player: usize,
+        offset: f32,
+        position: na::Point2<f32>,
+        speed: f32,
+    },
+    BallHitWall {
+        position: na::Point2<f32>,
+        speed: f32,
+    },
+    // Two balls in multi-ball bounced off each other, `speed` is the faster one
+    BallHitBall {
+        position: na::Point2<f32>,
+        speed: f32,
+    },
+    // `player` last hit the ball that picked the item up
+    PowerUpCollected {
+        kind: PowerUpKind,
+        player: usize,
+        position: na::Point2<f32>,
+    },
+    // The ball last hit by `player` broke a breakout brick worth `points` bonus
+    // points, they don't count towards the score
+    BrickBroken {
+        player: Option<usize>,
+        points: i32,
+        position: na::Point2<f32>,
+    },
+    // `conceder` let the ball through at `position`. The point goes to `scorer`,
+    // in a free-for-all that's whoever touched the ball last if anyone did.
+    PointScored {
+        scorer: Option<usize>,
+        conceder: usize,
+        position: na::Point2<f32>,
+    },
+    // `server` is the player the ball travels away from
+    ServeStarted {
+        server: usize,
+    },
+    // A set of a multi set match was won, the match goes on
+    SetWon {
+        winner: usize,
+    },
+    MatchWon {
+        winner: usize,
+    },
+}
+
+// Subsystems that react to game events, e.g. audio
+pub trait EventListener {
+    fn on_event(&mut self, ctx: &mut Context, settings: &Settings, event: &GameEvent);
+}
@@ -0,0 +1,104 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mode::{Difficulty, GameMode},
+    storage::Storage,
+    Context,
+};
+
+const HIGH_SCORES_PATH: &str = "/highscores.toml";
+pub const TABLE_SIZE: usize = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: i32,
+    // Seconds since the unix epoch
+    pub achieved_at: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScoreTable {
+    pub mode: String,
+    pub difficulty: Difficulty,
+    pub entries: Vec<HighScore>,
+}
+
+// Top scores for every solo mode and difficulty
+#[derive(Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub tables: Vec<HighScoreTable>,
+}
+
+impl HighScores {
+    pub fn load(ctx: &mut Context) -> Self {
+        Storage::load(ctx, HIGH_SCORES_PATH)
+    }
+
+    pub fn save(&self, ctx: &mut Context) {
+        if let Err(error) = Storage::save(ctx, HIGH_SCORES_PATH, self) {
+            eprintln!("Could not save high scores: {}", error);
+        }
+    }
+
+    pub fn entries(&self, mode: GameMode) -> &[HighScore] {
+        self.tables
+            .iter()
+            .find(|table| table.mode == mode.name() && Some(table.difficulty) == mode.difficulty())
+            .map(|table| table.entries.as_slice())
+            .unwrap_or(&[])
+    }
+
+    // A score makes the table if there's room or it beats the lowest entry
+    pub fn qualifies(&self, mode: GameMode, score: i32) -> bool {
+        let entries = self.entries(mode);
+        score > 0 && (entries.len() < TABLE_SIZE || entries.iter().any(|entry| score > entry.score))
+    }
+
+    // Returns the rank of the new entry, ties go below existing entries
+    pub fn insert(&mut self, mode: GameMode, name: &str, score: i32) -> Option<usize> {
+        let difficulty = mode.difficulty()?;
+        let index = match self
+            .tables
+            .iter()
+            .position(|table| table.mode == mode.name() && table.difficulty == difficulty)
+        {
+            Some(index) => index,
+            None => {
+                self.tables.push(HighScoreTable {
+                    mode: String::from(mode.name()),
+                    difficulty,
+                    entries: Vec::new(),
+                });
+                self.tables.len() - 1
+            }
+        };
+
+        let achieved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        let entries = &mut self.tables[index].entries;
+        let rank = entries
+            .iter()
+            .position(|entry| score > entry.score)
+            .unwrap_or(entries.len());
+        entries.insert(
+            rank,
+            HighScore {
+                name: String::from(name),
+                score,
+                achieved_at,
+            },
+        );
+        entries.truncate(TABLE_SIZE);
+
+        if rank < TABLE_SIZE {
+            Some(rank)
+        } else {
+            None
+        }
+    }
+}
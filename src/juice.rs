@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+
+use rand::{thread_rng, Rng};
+
+use crate::{
+    events::{EventListener, GameEvent},
+    graphics, na,
+    settings::Settings,
+    Context,
+};
+
+const SHAKE_DURATION: f32 = 0.25;
+// Pixels of shake per unit of ball speed at impact
+const SHAKE_PER_SPEED: f32 = 0.012;
+const MAX_SHAKE: f32 = 12.;
+const HIT_STOP_DURATION: f32 = 0.05;
+const TRAIL_LENGTH: usize = 8;
+const SQUASH_DURATION: f32 = 0.15;
+const SQUASH_AMOUNT: f32 = 0.4;
+
+// Optional game feel effects: screen shake, hit-stop, ball trail and squash
+pub struct Juice {
+    shake_timer: f32,
+    shake_strength: f32,
+    hit_stop_timer: f32,
+    // Ball positions of the last few ticks, newest first
+    trail: VecDeque<Vec<na::Point2<f32>>>,
+    squash_timer: f32,
+    // Squash along the x axis for paddle hits, along the y axis for walls
+    squash_horizontal: bool,
+}
+
+impl Juice {
+    pub fn new() -> Self {
+        Self {
+            shake_timer: 0.,
+            shake_strength: 0.,
+            hit_stop_timer: 0.,
+            trail: VecDeque::with_capacity(TRAIL_LENGTH),
+            squash_timer: 0.,
+            squash_horizontal: true,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    // Play is frozen for a moment after a paddle hit
+    pub fn is_hit_stopped(&self) -> bool {
+        self.hit_stop_timer > 0.
+    }
+
+    pub fn update(&mut self, settings: &Settings, dt: f32, ball_positions: Vec<na::Point2<f32>>) {
+        self.shake_timer = (self.shake_timer - dt).max(0.);
+        self.squash_timer = (self.squash_timer - dt).max(0.);
+
+        if self.is_hit_stopped() {
+            self.hit_stop_timer = (self.hit_stop_timer - dt).max(0.);
+            return;
+        }
+
+        if settings.ball_trail {
+            if self.trail.len() == TRAIL_LENGTH {
+                self.trail.pop_back();
+            }
+            self.trail.push_front(ball_positions);
+        } else {
+            self.trail.clear();
+        }
+    }
+
+    fn shake(&mut self, speed: f32, factor: f32) {
+        let strength = (speed * SHAKE_PER_SPEED * factor).min(MAX_SHAKE);
+        if self.shake_timer <= 0. || strength > self.shake_strength {
+            self.shake_strength = strength;
+        }
+        self.shake_timer = SHAKE_DURATION;
+    }
+
+    fn squash(&mut self, horizontal: bool) {
+        self.squash_timer = SQUASH_DURATION;
+        self.squash_horizontal = horizontal;
+    }
+
+    // Random camera offset, fading out as the shake runs out
+    pub fn shake_offset(&self) -> na::Vector2<f32> {
+        if self.shake_timer <= 0. {
+            return na::Vector2::new(0., 0.);
+        }
+
+        let strength = self.shake_strength * (self.shake_timer / SHAKE_DURATION);
+        let mut rng = thread_rng();
+        na::Vector2::new(
+            rng.gen_range(-strength, strength),
+            rng.gen_range(-strength, strength),
+        )
+    }
+
+    // Scale applied to the ball, squashed along the hit axis and stretched along the other
+    pub fn ball_scale(&self) -> na::Vector2<f32> {
+        let amount = SQUASH_AMOUNT * (self.squash_timer / SQUASH_DURATION);
+        if self.squash_horizontal {
+            na::Vector2::new(1. - amount, 1. + amount)
+        } else {
+            na::Vector2::new(1. + amount, 1. - amount)
+        }
+    }
+
+    // Draw fading copies of the ball mesh along the recent positions of every ball
+    pub fn draw_trail(&self, ctx: &mut Context, ball_mesh: &graphics::Mesh) {
+        for (index, positions) in self.trail.iter().enumerate().skip(1) {
+            let fade = 1. - index as f32 / TRAIL_LENGTH as f32;
+            for position in positions {
+                let draw_param = graphics::DrawParam::default()
+                    .dest(*position)
+                    .scale(na::Vector2::new(fade, fade))
+                    .color(graphics::Color::new(1., 1., 1., fade * 0.4));
+                graphics::draw(ctx, ball_mesh, draw_param).unwrap();
+            }
+        }
+    }
+}
+
+impl EventListener for Juice {
+    fn on_event(&mut self, _ctx: &mut Context, settings: &Settings, event: &GameEvent) {
+        match event {
+            GameEvent::BallHitPaddle { speed, .. } => {
+                if settings.screen_shake {
+                    self.shake(*speed, 1.);
+                }
+                if settings.hit_stop {
+                    self.hit_stop_timer = HIT_STOP_DURATION;
+                }
+                if settings.squash_stretch {
+                    self.squash(true);
+                }
+            }
+            GameEvent::BallHitWall { speed, .. } => {
+                if settings.screen_shake {
+                    self.shake(*speed, 0.5);
+                }
+                if settings.squash_stretch {
+                    self.squash(false);
+                }
+            }
+            GameEvent::PointScored { .. } if settings.screen_shake => {
+                self.shake(MAX_SHAKE / SHAKE_PER_SPEED, 1.)
+            }
+            GameEvent::ServeStarted { .. } => self.trail.clear(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{storage::Storage, Context};
+
+const LADDER_PATH: &str = "/ladder.toml";
+const INITIAL_RATING: f32 = 1200.;
+// How far a single result can move a rating
+const K_FACTOR: f32 = 32.;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Rating {
+    pub name: String,
+    pub rating: f32,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl Rating {
+    fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            rating: INITIAL_RATING,
+            wins: 0,
+            losses: 0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub winner: String,
+    pub loser: String,
+    pub winner_score: i32,
+    pub loser_score: i32,
+    // Seconds since the unix epoch
+    pub played_at: u64,
+}
+
+// Wins and losses against a single opponent
+pub struct HeadToHead {
+    pub opponent: String,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+// Elo ratings and the history of every finished match between profiles
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Ladder {
+    pub ratings: Vec<Rating>,
+    pub matches: Vec<MatchResult>,
+}
+
+impl Ladder {
+    // A rating edited by hand into something that isn't a number starts over
+    pub fn load(ctx: &mut Context) -> Self {
+        let mut ladder: Self = Storage::load(ctx, LADDER_PATH);
+        for rating in &mut ladder.ratings {
+            if !rating.rating.is_finite() {
+                rating.rating = INITIAL_RATING;
+            }
+        }
+        ladder
+    }
+
+    pub fn save(&self, ctx: &mut Context) {
+        if let Err(error) = Storage::save(ctx, LADDER_PATH, self) {
+            eprintln!("Could not save ladder: {}", error);
+        }
+    }
+
+    fn rating_index(&mut self, name: &str) -> usize {
+        if let Some(index) = self
+            .ratings
+            .iter()
+            .position(|rating| rating.name.eq_ignore_ascii_case(name))
+        {
+            return index;
+        }
+
+        self.ratings.push(Rating::new(name));
+        self.ratings.len() - 1
+    }
+
+    // Current rating of a player, someone who hasn't played yet starts at the initial rating
+    pub fn rating(&self, name: &str) -> f32 {
+        self.ratings
+            .iter()
+            .find(|rating| rating.name.eq_ignore_ascii_case(name))
+            .map_or(INITIAL_RATING, |rating| rating.rating)
+    }
+
+    // Store the result and move both ratings by the standard Elo formula
+    pub fn record(&mut self, winner: &str, loser: &str, winner_score: i32, loser_score: i32) {
+        let (winner_index, loser_index) = (self.rating_index(winner), self.rating_index(loser));
+        let (winner_rating, loser_rating) = (
+            self.ratings[winner_index].rating,
+            self.ratings[loser_index].rating,
+        );
+
+        let expected = 1. / (1. + 10f32.powf((loser_rating - winner_rating) / 400.));
+        let change = K_FACTOR * (1. - expected);
+
+        self.ratings[winner_index].rating += change;
+        self.ratings[winner_index].wins += 1;
+        self.ratings[loser_index].rating -= change;
+        self.ratings[loser_index].losses += 1;
+
+        let played_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        self.matches.push(MatchResult {
+            winner: String::from(winner),
+            loser: String::from(loser),
+            winner_score,
+            loser_score,
+            played_at,
+        });
+    }
+
+    // Highest rating first, a rating that isn't a number goes to the bottom
+    pub fn standings(&self) -> Vec<&Rating> {
+        let mut standings: Vec<&Rating> = self.ratings.iter().collect();
+        standings.sort_by(|a, b| {
+            (b.rating.is_finite().cmp(&a.rating.is_finite())).then(b.rating.total_cmp(&a.rating))
+        });
+        standings
+    }
+
+    pub fn head_to_head(&self, name: &str) -> Vec<HeadToHead> {
+        let mut records: Vec<HeadToHead> = Vec::new();
+
+        for result in &self.matches {
+            let (opponent, won) = if result.winner.eq_ignore_ascii_case(name) {
+                (&result.loser, true)
+            } else if result.loser.eq_ignore_ascii_case(name) {
+                (&result.winner, false)
+            } else {
+                continue;
+            };
+
+            let index = match records
+                .iter()
+                .position(|record| record.opponent.eq_ignore_ascii_case(opponent))
+            {
+                Some(index) => index,
+                None => {
+                    records.push(HeadToHead {
+                        opponent: opponent.clone(),
+                        wins: 0,
+                        losses: 0,
+                    });
+                    records.len() - 1
+                }
+            };
+
+            if won {
+                records[index].wins += 1;
+            } else {
+                records[index].losses += 1;
+            }
+        }
+
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rating_changes_sum_to_zero() {
+        let mut ladder = Ladder::default();
+        ladder.record("Alice", "Bob", 5, 3);
+        ladder.record("Bob", "Carol", 5, 1);
+        ladder.record("Carol", "Alice", 5, 4);
+
+        let total: f32 = ladder.ratings.iter().map(|rating| rating.rating).sum();
+        assert!((total - INITIAL_RATING * 3.).abs() < 0.01);
+    }
+
+    #[test]
+    fn upsets_move_ratings_more() {
+        let mut ladder = Ladder::default();
+        for _ in 0..5 {
+            ladder.record("Favourite", "Underdog", 5, 0);
+        }
+        let (favourite, underdog) = (ladder.rating("Favourite"), ladder.rating("Underdog"));
+
+        let mut expected = ladder.clone();
+        expected.record("Favourite", "Underdog", 5, 0);
+        let expected_gain = expected.rating("Favourite") - favourite;
+
+        ladder.record("Underdog", "Favourite", 5, 0);
+        let upset_gain = ladder.rating("Underdog") - underdog;
+
+        assert!(expected_gain > 0.);
+        assert!(upset_gain > expected_gain);
+    }
+
+    #[test]
+    fn standings_put_non_finite_ratings_last() {
+        let mut ladder = Ladder::default();
+        for (name, value) in [("Low", 1100.), ("Broken", f32::NAN), ("High", 1300.)].iter() {
+            let mut entry = Rating::new(name);
+            entry.rating = *value;
+            ladder.ratings.push(entry);
+        }
+        let names: Vec<&str> = ladder
+            .standings()
+            .iter()
+            .map(|rating| rating.name.as_str())
+            .collect();
+        assert_eq!(names, ["High", "Low", "Broken"]);
+    }
+
+    #[test]
+    fn head_to_head_counts_both_ways() {
+        let mut ladder = Ladder::default();
+        ladder.record("Alice", "Bob", 5, 3);
+        ladder.record("bob", "Alice", 5, 2);
+        ladder.record("Alice", "Bob", 5, 4);
+        ladder.record("Alice", "Carol", 5, 0);
+
+        let records = ladder.head_to_head("alice");
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].wins, records[0].losses), (2, 1));
+        assert_eq!(records[1].opponent, "Carol");
+        assert_eq!((records[1].wins, records[1].losses), (1, 0));
+    }
+}
@@ -1,50 +1,95 @@
 use std::{env, path};
 
-use ggez;
-use ggez::event;
+use ggez::audio;
+use ggez::conf;
+use ggez::event::{self, Axis, Button};
+use ggez::filesystem;
 use ggez::graphics;
 use ggez::graphics::{DrawParam, Text};
+use ggez::input::gamepad::{self, GamepadId};
 use ggez::input::keyboard::{self, KeyCode, KeyMods};
+use ggez::input::mouse::{self, MouseButton};
 use ggez::nalgebra as na;
+use ggez::timer;
 use ggez::{Context, GameResult};
 
+mod ai;
+mod arena;
 mod ball;
+mod bracket;
+mod bricks;
+mod clock;
 mod collidable;
+mod events;
+mod highscores;
+mod juice;
+mod ladder;
+mod mode;
+mod particles;
 mod player;
+mod powerups;
+mod profile;
 mod scenes;
+mod scoring;
+mod settings;
+mod side;
+mod sound;
+mod stats;
+mod storage;
+mod synth;
+mod ui;
 mod util;
+mod viewport;
 mod world;
 
 use ball::Ball;
-use player::{Controls, Paddle};
+use player::{ControlMode, Controls, Paddle};
 use scenes::*;
+use viewport::{Viewport, ARENA_HEIGHT, ARENA_WIDTH};
 use world::World;
 
+// Color of the bars around the arena when the window's aspect ratio differs
+const LETTERBOX_COLOR: [f32; 4] = [0.08, 0.08, 0.08, 1.0];
+
 struct MainState {
     world: World,
     current_scene: Box<dyn Scene<World>>,
+    arena_background: graphics::Mesh,
 }
 
 impl MainState {
     pub fn new(ctx: &mut Context) -> Self {
-        let initial_scene = Box::new(scenes::menu::MenuScene::new(ctx));
-
         let max_score = 5;
         let world = World::new(ctx, max_score);
 
+        let initial_scene = Box::new(scenes::menu::MenuScene::new(ctx, &world));
+
+        let arena_background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            Viewport::arena_rect(),
+            graphics::BLACK,
+        )
+        .unwrap();
+
         MainState {
             world,
             current_scene: initial_scene,
+            arena_background,
         }
     }
 }
 
 impl event::EventHandler for MainState {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
-        let dt = ggez::timer::delta(ctx).as_secs_f32();
+        let dt = timer::delta(ctx).as_secs_f32();
         self.world.update_delta_time(dt);
 
         if let Some(next_scene) = self.current_scene.update(ctx, &mut self.world) {
+            // Scene changes are logged in debug builds
+            if cfg!(debug_assertions) {
+                eprintln!("{} -> {}", self.current_scene.name(), next_scene.name());
+            }
             self.current_scene = next_scene
         }
 
@@ -52,7 +97,18 @@ impl event::EventHandler for MainState {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        graphics::clear(ctx, [0.0, 0.0, 0.0, 1.0].into());
+        // Scenes can change the arena size, e.g. a match on a bigger arena
+        let (width, height) = self.current_scene.arena_size(&self.world);
+        let (window_width, window_height) = graphics::drawable_size(ctx);
+        Viewport::fit(ctx, window_width, window_height, width, height)?;
+
+        graphics::clear(ctx, LETTERBOX_COLOR.into());
+        let background_scale = na::Vector2::new(width / ARENA_WIDTH, height / ARENA_HEIGHT);
+        graphics::draw(
+            ctx,
+            &self.arena_background,
+            DrawParam::default().scale(background_scale),
+        )?;
 
         self.current_scene.draw(ctx, &mut self.world);
 
@@ -74,6 +130,38 @@ impl event::EventHandler for MainState {
         self.current_scene
             .input(ctx, &mut self.world, keycode, true, repeat)
     }
+
+    fn text_input_event(&mut self, ctx: &mut Context, character: char) {
+        self.current_scene
+            .text_input(ctx, &mut self.world, character)
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        let position = Viewport::to_arena(ctx, x, y);
+        self.current_scene
+            .mouse_motion(ctx, &mut self.world, position.x, position.y)
+    }
+
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let position = Viewport::to_arena(ctx, x, y);
+        self.current_scene
+            .mouse_button_down(ctx, &mut self.world, button, position.x, position.y)
+    }
+
+    // Gamepads can't be listed up front, they're picked up the first time they're used
+    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, _button: Button, id: GamepadId) {
+        self.world.connect_gamepad(id);
+    }
+
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, _axis: Axis, _value: f32, id: GamepadId) {
+        self.world.connect_gamepad(id);
+    }
+
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let position = Viewport::to_arena(ctx, x, y);
+        self.current_scene
+            .mouse_button_up(ctx, &mut self.world, button, position.x, position.y)
+    }
 }
 
 fn main() -> GameResult {
@@ -85,7 +173,8 @@ fn main() -> GameResult {
         path::PathBuf::from("./resources")
     };
 
-    let c = ggez::conf::Conf::new();
+    let mut c = conf::Conf::new();
+    c.window_mode = c.window_mode.resizable(true);
     let (ref mut ctx, ref mut event_loop) = ggez::ContextBuilder::new("Pong", "Troligtvis")
         .add_resource_path(resource_dir)
         .conf(c)
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{side::Side, stats::MatchStats};
+
+// Points the computer needs to end a survival run
+pub const SURVIVAL_LIVES: i32 = 3;
+// Misses allowed in wall practice
+pub const PRACTICE_LIVES: i32 = 3;
+// Goals each player can let in before they're out of a free-for-all
+pub const FREE_FOR_ALL_LIVES: i32 = 3;
+// How far in front of the back paddles the forward paddles of doubles play
+const FORWARD_DEPTH: f32 = 220.;
+// Goal gap in the end walls for air hockey on an arena without goal posts
+const AIR_HOCKEY_GOAL_HEIGHT: f32 = 180.;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+// When multi-ball puts another ball in play
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BallLaunch {
+    // An extra ball joins the rally every so many seconds
+    Every(i32),
+    // Every point scored adds a ball to the next serve
+    OnPoint,
+}
+
+impl BallLaunch {
+    pub const ALL: [BallLaunch; 4] = [
+        BallLaunch::Every(5),
+        BallLaunch::Every(10),
+        BallLaunch::Every(20),
+        BallLaunch::OnPoint,
+    ];
+
+    pub fn name(self) -> String {
+        match self {
+            BallLaunch::Every(seconds) => format!("Every {} s", seconds),
+            BallLaunch::OnPoint => String::from("On each point"),
+        }
+    }
+}
+
+// Where a player of a mode plays
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Assignment {
+    // The edge with the goal the player defends
+    pub side: Side,
+    pub team: usize,
+    // How far in front of the goal the paddle plays
+    pub depth: f32,
+}
+
+impl Assignment {
+    fn new(side: Side, team: usize) -> Self {
+        Self {
+            side,
+            team,
+            depth: 0.,
+        }
+    }
+
+    fn forward(mut self, depth: f32) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameMode {
+    // Two local players
+    Versus,
+    // Player 1 against the computer, first to the max score
+    VersusAi(Difficulty),
+    // Player 1 scores as many points as possible before the computer takes all lives
+    Survival(Difficulty),
+    // Two local players, the leader when the clock runs out wins
+    Timed,
+    // Two local players with more balls launched as the match goes on
+    MultiBall,
+    // Player 1 alone against a wall, the score is the number of returns
+    Practice(Difficulty),
+    // Four local players, one on each edge, the last one left wins
+    FreeForAll,
+    // Two teams with a back and a forward paddle each, every seat a person or the computer
+    Doubles,
+    // Two local players with mallets that go anywhere in their own half and a sliding puck
+    AirHockey,
+    // Two local players either side of a brick wall, goals through the gaps they
+    // break open score and the bricks add bonus points
+    Breakout,
+}
+
+impl GameMode {
+    // Modes with a high score table, for the given difficulty
+    pub fn solo_modes(difficulty: Difficulty) -> Vec<GameMode> {
+        vec![
+            GameMode::VersusAi(difficulty),
+            GameMode::Survival(difficulty),
+            GameMode::Practice(difficulty),
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Versus => "Versus",
+            GameMode::VersusAi(_) => "Versus computer",
+            GameMode::Survival(_) => "Survival",
+            GameMode::Timed => "Timed match",
+            GameMode::MultiBall => "Multi-ball",
+            GameMode::Practice(_) => "Wall practice",
+            GameMode::FreeForAll => "Free-for-all",
+            GameMode::Doubles => "Doubles",
+            GameMode::AirHockey => "Air hockey",
+            GameMode::Breakout => "Breakout",
+        }
+    }
+
+    // Mode name with the difficulty, e.g. "Survival (Hard)"
+    pub fn describe(self) -> String {
+        match self.difficulty() {
+            Some(difficulty) => format!("{} ({})", self.name(), difficulty.name()),
+            None => String::from(self.name()),
+        }
+    }
+
+    pub fn difficulty(self) -> Option<Difficulty> {
+        match self {
+            GameMode::Versus
+            | GameMode::Timed
+            | GameMode::MultiBall
+            | GameMode::FreeForAll
+            | GameMode::Doubles
+            | GameMode::AirHockey
+            | GameMode::Breakout => None,
+            GameMode::VersusAi(difficulty)
+            | GameMode::Survival(difficulty)
+            | GameMode::Practice(difficulty) => Some(difficulty),
+        }
+    }
+
+    // Every player of the mode, player 1 first. The back paddles of doubles come
+    // first, then the forward ones.
+    pub fn assignments(self) -> Vec<Assignment> {
+        match self {
+            GameMode::FreeForAll => Side::ALL
+                .iter()
+                .enumerate()
+                .map(|(team, side)| Assignment::new(*side, team))
+                .collect(),
+            GameMode::Doubles => vec![
+                Assignment::new(Side::Left, 0),
+                Assignment::new(Side::Right, 1),
+                Assignment::new(Side::Left, 0).forward(FORWARD_DEPTH),
+                Assignment::new(Side::Right, 1).forward(FORWARD_DEPTH),
+            ],
+            GameMode::Practice(_) => vec![Assignment::new(Side::Left, 0)],
+            _ => vec![
+                Assignment::new(Side::Left, 0),
+                Assignment::new(Side::Right, 1),
+            ],
+        }
+    }
+
+    pub fn player_count(self) -> usize {
+        self.assignments().len()
+    }
+
+    // Everyone plays for themselves in a free-for-all, the wall of wall practice
+    // is a team of its own without players
+    pub fn team_count(self) -> usize {
+        match self {
+            GameMode::FreeForAll => self.player_count(),
+            _ => 2,
+        }
+    }
+
+    // Edges closed off by a wall for the whole match
+    pub fn walls(self) -> &'static [Side] {
+        match self {
+            GameMode::Practice(_) => &[Side::Right],
+            _ => &[],
+        }
+    }
+
+    // The points are the goals each team let in rather than the ones it scored
+    pub fn counts_goals_against(self) -> bool {
+        self.is_free_for_all()
+    }
+
+    pub fn is_air_hockey(self) -> bool {
+        self == GameMode::AirHockey
+    }
+
+    // Height of the goals in the end walls for arenas that leave the edges open
+    pub fn goal_height(self) -> Option<f32> {
+        match self {
+            GameMode::AirHockey => Some(AIR_HOCKEY_GOAL_HEIGHT),
+            _ => None,
+        }
+    }
+
+    // A wall of bricks stands in the middle of the arena
+    pub fn has_bricks(self) -> bool {
+        self == GameMode::Breakout
+    }
+
+    // Items only show up where they don't get in the way of the mode
+    pub fn has_power_ups(self) -> bool {
+        !matches!(self, GameMode::Practice(_) | GameMode::AirHockey)
+    }
+
+    // Seats can be taken by the computer instead of a profile
+    pub fn has_computer_seats(self) -> bool {
+        self == GameMode::Doubles
+    }
+
+    pub fn is_free_for_all(self) -> bool {
+        self == GameMode::FreeForAll
+    }
+
+    // Strength of the computer playing as player 2, if there is one
+    pub fn computer_opponent(self) -> Option<Difficulty> {
+        match self {
+            GameMode::VersusAi(difficulty) | GameMode::Survival(difficulty) => Some(difficulty),
+            _ => None,
+        }
+    }
+
+    // Lives of player 1 in the modes played until the last life is lost
+    pub fn lives(self) -> Option<i32> {
+        match self {
+            GameMode::Survival(_) => Some(SURVIVAL_LIVES),
+            GameMode::Practice(_) => Some(PRACTICE_LIVES),
+            _ => None,
+        }
+    }
+
+    // Player 1 plays alone against a wall
+    pub fn is_practice(self) -> bool {
+        matches!(self, GameMode::Practice(_))
+    }
+
+    // Ball speed gained every second, the ball only speeds up in wall practice
+    pub fn ball_acceleration(self) -> f32 {
+        match self {
+            GameMode::Practice(Difficulty::Easy) => 10.,
+            GameMode::Practice(Difficulty::Normal) => 20.,
+            GameMode::Practice(Difficulty::Hard) => 35.,
+            _ => 0.,
+        }
+    }
+
+    // Solo modes have one human player, player 2 is the computer or a wall
+    pub fn is_solo(self) -> bool {
+        self.difficulty().is_some()
+    }
+
+    // Score for the high score table, None if the match doesn't count
+    pub fn solo_score(self, stats: &MatchStats) -> Option<i32> {
+        let score = stats.score();
+        match self {
+            GameMode::Versus
+            | GameMode::Timed
+            | GameMode::MultiBall
+            | GameMode::FreeForAll
+            | GameMode::Doubles
+            | GameMode::AirHockey
+            | GameMode::Breakout => None,
+            // Winning margin against the computer
+            GameMode::VersusAi(_) if score[0] > score[1] => Some((score[0] - score[1]) as i32),
+            GameMode::VersusAi(_) => None,
+            GameMode::Survival(_) => Some(score[0] as i32),
+            GameMode::Practice(_) => Some(stats.hits[0] as i32),
+        }
+    }
+}
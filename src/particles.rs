@@ -0,0 +1,164 @@
+use rand::{thread_rng, Rng};
+
+use crate::{
+    events::{EventListener, GameEvent},
+    graphics,
+    graphics::spritebatch::SpriteBatch,
+    na,
+    settings::Settings,
+    Context,
+};
+
+const MAX_PARTICLES: usize = 1000;
+const PARTICLE_SIZE: f32 = 4.;
+const PARTICLE_SPEED: f32 = 220.;
+
+// Particle lifetimes selectable from the options menu, in seconds
+pub const LIFETIMES: [(&str, f32); 3] = [("Short", 0.3), ("Normal", 0.6), ("Long", 1.0)];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ParticleColor {
+    White,
+    Orange,
+    Rainbow,
+}
+
+impl ParticleColor {
+    pub const ALL: [ParticleColor; 3] = [
+        ParticleColor::White,
+        ParticleColor::Orange,
+        ParticleColor::Rainbow,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ParticleColor::White => "White",
+            ParticleColor::Orange => "Orange",
+            ParticleColor::Rainbow => "Rainbow",
+        }
+    }
+
+    fn pick(self) -> graphics::Color {
+        match self {
+            ParticleColor::White => graphics::WHITE,
+            ParticleColor::Orange => graphics::Color::from_rgb(192, 128, 64),
+            ParticleColor::Rainbow => {
+                // Fully saturated color with a random hue
+                let hue = thread_rng().gen_range(0., 6.);
+                let x = 1. - ((hue % 2.) - 1.0f32).abs();
+                let (r, g, b) = match hue as u32 {
+                    0 => (1., x, 0.),
+                    1 => (x, 1., 0.),
+                    2 => (0., 1., x),
+                    3 => (0., x, 1.),
+                    4 => (x, 0., 1.),
+                    _ => (1., 0., x),
+                };
+                graphics::Color::new(r, g, b, 1.)
+            }
+        }
+    }
+}
+
+struct Particle {
+    position: na::Point2<f32>,
+    velocity: na::Vector2<f32>,
+    age: f32,
+    lifetime: f32,
+    color: graphics::Color,
+}
+
+// Short lived squares bursting out of hits and goals, drawn in a single batch
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    batch: SpriteBatch,
+}
+
+impl ParticleSystem {
+    pub fn new(ctx: &mut Context) -> Self {
+        let image = graphics::Image::solid(ctx, 1, graphics::WHITE).unwrap();
+
+        Self {
+            particles: Vec::new(),
+            batch: SpriteBatch::new(image),
+        }
+    }
+
+    pub fn burst(&mut self, settings: &Settings, position: na::Point2<f32>, count: i32) {
+        let mut rng = thread_rng();
+        let (count, lifetime) = if settings.reduced_effects {
+            (count / 4, settings.particle_lifetime * 0.5)
+        } else {
+            (count, settings.particle_lifetime)
+        };
+
+        for _ in 0..count {
+            if self.particles.len() >= MAX_PARTICLES {
+                break;
+            }
+
+            let angle = rng.gen_range(0., std::f32::consts::PI * 2.);
+            let speed = rng.gen_range(0.3, 1.) * PARTICLE_SPEED;
+            self.particles.push(Particle {
+                position,
+                velocity: na::Vector2::new(angle.cos(), angle.sin()) * speed,
+                age: 0.,
+                lifetime: lifetime * rng.gen_range(0.5, 1.),
+                color: settings.particle_color.pick(),
+            });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for particle in self.particles.iter_mut() {
+            particle.position += particle.velocity * dt;
+            particle.age += dt;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) {
+        if self.particles.is_empty() {
+            return;
+        }
+
+        self.batch.clear();
+        for particle in &self.particles {
+            let mut color = particle.color;
+            color.a = 1. - particle.age / particle.lifetime;
+
+            self.batch.add(
+                graphics::DrawParam::new()
+                    .dest(particle.position)
+                    .offset(na::Point2::new(0.5, 0.5))
+                    .scale(na::Vector2::new(PARTICLE_SIZE, PARTICLE_SIZE))
+                    .color(color),
+            );
+        }
+
+        graphics::draw(ctx, &self.batch, graphics::DrawParam::default()).unwrap();
+    }
+}
+
+impl EventListener for ParticleSystem {
+    fn on_event(&mut self, _ctx: &mut Context, settings: &Settings, event: &GameEvent) {
+        let count = settings.particle_count;
+        match event {
+            GameEvent::BallHitPaddle { position, .. } => self.burst(settings, *position, count),
+            GameEvent::BallHitWall { position, .. } | GameEvent::BallHitBall { position, .. } => {
+                self.burst(settings, *position, count / 2)
+            }
+            GameEvent::PowerUpCollected { position, .. }
+            | GameEvent::BrickBroken { position, .. } => self.burst(settings, *position, count * 2),
+            GameEvent::PointScored { position, .. } if !settings.reduced_effects => {
+                self.burst(settings, *position, count * 6)
+            }
+            _ => {}
+        }
+    }
+}
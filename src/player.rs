@@ -1,20 +1,87 @@
-use crate::{collidable::Collidable, graphics, keyboard, na, util::Util, Context, KeyCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::Ai,
+    collidable::Collidable,
+    gamepad::{self, GamepadId},
+    graphics, keyboard,
+    mode::Assignment,
+    na,
+    side::Side,
+    util::Util,
+    viewport::{Viewport, ARENA_HEIGHT},
+    Axis, Ball, Button, Context, KeyCode,
+};
 
 // Paddle data
-const PADDLE_HEIGHT: f32 = 100.0;
-const PADDLE_WIDTH: f32 = 20.0;
+pub const PADDLE_HEIGHT: f32 = 100.0;
+pub const PADDLE_WIDTH: f32 = 20.0;
 const PADDLE_HEIGHT_HALF: f32 = PADDLE_HEIGHT * 0.5;
 pub const PADDLE_WIDTH_HALF: f32 = PADDLE_WIDTH * 0.5;
 const PADDLE_SPEED: f32 = 600.0;
+// Air hockey mallet data
+const MALLET_RADIUS: f32 = 32.0;
+const MALLET_SPEED: f32 = 700.0;
+// Stick travel ignored around the centre, a worn stick rarely rests at zero
+const STICK_DEAD_ZONE: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ControlMode {
+    Keyboard,
+    // The paddle follows the cursor along its edge
+    Mouse,
+    // Left stick or d-pad of a gamepad
+    Gamepad,
+}
 
+// Paddles on the top and bottom edges go left with the up key and right with the down key
 pub struct Controls {
+    mode: ControlMode,
     up_key: KeyCode,
     down_key: KeyCode,
+    // Keys that move a mallet left and right
+    side_keys: Option<(KeyCode, KeyCode)>,
+    // Which of the connected gamepads, in the order they were first used
+    gamepad: usize,
 }
 
 impl Controls {
     pub fn new(up_key: KeyCode, down_key: KeyCode) -> Self {
-        Controls { up_key, down_key }
+        Controls {
+            mode: ControlMode::Keyboard,
+            up_key,
+            down_key,
+            side_keys: None,
+            gamepad: 0,
+        }
+    }
+
+    pub fn with_side_keys(mut self, left_key: KeyCode, right_key: KeyCode) -> Self {
+        self.side_keys = Some((left_key, right_key));
+        self
+    }
+
+    pub fn with_mode(mut self, mode: ControlMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_gamepad(mut self, gamepad: usize) -> Self {
+        self.gamepad = gamepad;
+        self
+    }
+
+    // Human readable description of the active bindings, e.g. "W / S"
+    pub fn describe(&self) -> String {
+        match (self.mode, self.side_keys) {
+            (ControlMode::Keyboard, Some((left_key, right_key))) => format!(
+                "{:?} / {:?} / {:?} / {:?}",
+                self.up_key, left_key, self.down_key, right_key
+            ),
+            (ControlMode::Keyboard, None) => format!("{:?} / {:?}", self.up_key, self.down_key),
+            (ControlMode::Mouse, _) => String::from("Mouse"),
+            (ControlMode::Gamepad, _) => format!("Gamepad {}", self.gamepad + 1),
+        }
     }
 }
 
@@ -24,69 +91,238 @@ enum Direction {
 }
 
 pub struct Player {
-    score: i32,
     controls: Controls,
+    // Set when the computer controls this player
+    ai: Option<Ai>,
+    // Up moves the paddle down and the other way round
+    pub reversed: bool,
     pub paddle: Paddle,
+    // The edge the player defends
+    pub side: Side,
+    pub team: usize,
     name: String,
+    color: graphics::Color,
 }
 
 impl Player {
     pub fn new(
         ctx: &mut Context,
         controls: Controls,
+        assignment: Assignment,
         position: na::Vector2<f32>,
         name: String,
+        color: graphics::Color,
     ) -> Self {
-        let rect = graphics::Rect::new(
-            -PADDLE_WIDTH_HALF,
-            -PADDLE_HEIGHT_HALF,
-            PADDLE_WIDTH,
-            PADDLE_HEIGHT,
-        );
+        let side = assignment.side;
+        let rect = if side.is_horizontal() {
+            graphics::Rect::new(
+                -PADDLE_HEIGHT_HALF,
+                -PADDLE_WIDTH_HALF,
+                PADDLE_HEIGHT,
+                PADDLE_WIDTH,
+            )
+        } else {
+            graphics::Rect::new(
+                -PADDLE_WIDTH_HALF,
+                -PADDLE_HEIGHT_HALF,
+                PADDLE_WIDTH,
+                PADDLE_HEIGHT,
+            )
+        };
 
         let paddle = Paddle::new(ctx, position, rect, PADDLE_SPEED);
 
         Self {
-            score: 0,
             controls,
+            ai: None,
+            reversed: false,
             paddle,
+            side,
+            team: assignment.team,
             name,
+            color,
+        }
+    }
+
+    pub fn set_ai(&mut self, ai: Ai) {
+        self.ai = Some(ai);
+    }
+
+    // Swap the paddle for a round mallet that goes anywhere in `area`
+    pub fn use_mallet(&mut self, ctx: &mut Context, area: graphics::Rect) {
+        let position = self.paddle.position.coords;
+        self.paddle = Paddle::mallet(ctx, position, MALLET_RADIUS, MALLET_SPEED, area);
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, dt: f32, balls: &[Ball], gamepads: &[GamepadId]) {
+        let before = self.paddle.position;
+        if self.paddle.area.is_some() {
+            self.control_mallet(ctx, dt, gamepads);
+        } else {
+            self.control(ctx, dt, balls, gamepads);
+        }
+        if dt > 0. {
+            self.paddle.velocity = (self.paddle.position - before) / dt;
         }
     }
 
-    pub fn update(&mut self, ctx: &mut Context, dt: f32) {
-        if keyboard::is_key_pressed(ctx, self.controls.up_key) {
-            self.paddle.move_direction(ctx, Direction::Up, dt);
+    fn control(&mut self, ctx: &mut Context, dt: f32, balls: &[Ball], gamepads: &[GamepadId]) {
+        if let Some(ai) = &mut self.ai {
+            let target = ai.target(self.side, self.paddle.position, balls);
+            let speed_factor = ai.speed_factor();
+            let target = self.steer(target);
+            // A slower computer covers less ground each tick
+            self.paddle.move_towards(target, dt * speed_factor);
+            return;
         }
 
-        if keyboard::is_key_pressed(ctx, self.controls.down_key) {
-            self.paddle.move_direction(ctx, Direction::Down, dt);
+        let (up, down) = if self.reversed {
+            (Direction::Down, Direction::Up)
+        } else {
+            (Direction::Up, Direction::Down)
+        };
+
+        match self.controls.mode {
+            ControlMode::Keyboard => {
+                if keyboard::is_key_pressed(ctx, self.controls.up_key) {
+                    self.paddle.move_direction(up, dt);
+                }
+
+                if keyboard::is_key_pressed(ctx, self.controls.down_key) {
+                    self.paddle.move_direction(down, dt);
+                }
+            }
+            ControlMode::Gamepad => {
+                // Not connected yet, the pad joins the list once it's touched
+                let id = match gamepads.get(self.controls.gamepad) {
+                    Some(id) => *id,
+                    None => return,
+                };
+                let pad = gamepad::gamepad(ctx, id);
+
+                // Up on the stick is positive, the top and bottom paddles use left and right
+                let (stick, towards_start, towards_end) = if self.side.is_horizontal() {
+                    let stick = -pad.value(Axis::LeftStickX);
+                    (stick, Button::DPadLeft, Button::DPadRight)
+                } else {
+                    let stick = pad.value(Axis::LeftStickY);
+                    (stick, Button::DPadUp, Button::DPadDown)
+                };
+
+                if stick > STICK_DEAD_ZONE || pad.is_pressed(towards_start) {
+                    self.paddle.move_direction(up, dt);
+                } else if stick < -STICK_DEAD_ZONE || pad.is_pressed(towards_end) {
+                    self.paddle.move_direction(down, dt);
+                }
+            }
+            ControlMode::Mouse => {
+                let mouse = Viewport::mouse_position(ctx);
+                let target = self.steer(self.side.along(mouse.coords));
+                self.paddle.move_towards(target, dt);
+            }
         }
     }
 
-    pub fn draw(&self, ctx: &mut Context) {
-        let mut draw_param = graphics::DrawParam::default();
-        draw_param.dest = self.paddle.get_position().into();
+    // Mallets move in any direction, the mouse drags them along to the cursor
+    fn control_mallet(&mut self, ctx: &mut Context, dt: f32, gamepads: &[GamepadId]) {
+        let mut direction = na::Vector2::new(0., 0.);
+        match self.controls.mode {
+            ControlMode::Keyboard => {
+                let pressed = |key| keyboard::is_key_pressed(ctx, key);
+                if let Some((left_key, right_key)) = self.controls.side_keys {
+                    direction.x = Util::axis(pressed(left_key), pressed(right_key));
+                }
+                direction.y = Util::axis(
+                    pressed(self.controls.up_key),
+                    pressed(self.controls.down_key),
+                );
+            }
+            ControlMode::Gamepad => {
+                let id = match gamepads.get(self.controls.gamepad) {
+                    Some(id) => *id,
+                    None => return,
+                };
+                let pad = gamepad::gamepad(ctx, id);
 
-        graphics::draw(ctx, self.paddle.get_mesh(), draw_param).unwrap();
+                // Up on the stick is positive, the arena's y axis points down
+                let stick =
+                    na::Vector2::new(pad.value(Axis::LeftStickX), -pad.value(Axis::LeftStickY));
+                if stick.norm() > STICK_DEAD_ZONE {
+                    direction = stick;
+                } else {
+                    direction.x = Util::axis(
+                        pad.is_pressed(Button::DPadLeft),
+                        pad.is_pressed(Button::DPadRight),
+                    );
+                    direction.y = Util::axis(
+                        pad.is_pressed(Button::DPadUp),
+                        pad.is_pressed(Button::DPadDown),
+                    );
+                }
+            }
+            ControlMode::Mouse => {
+                let mouse = Viewport::mouse_position(ctx);
+                self.paddle.move_to(mouse, dt);
+                return;
+            }
+        }
+
+        if self.reversed {
+            direction = -direction;
+        }
+        self.paddle.move_free(direction, dt);
     }
 
-    pub fn increment_score(&mut self) {
-        self.score += 1;
+    // Reversed controls send the paddle away from where it's steered
+    fn steer(&self, target: f32) -> f32 {
+        if self.reversed {
+            self.paddle.along() * 2. - target
+        } else {
+            target
+        }
     }
 
-    pub fn get_score(&self) -> i32 {
-        self.score
+    pub fn draw(&self, ctx: &mut Context) {
+        let scale = if self.paddle.horizontal {
+            na::Vector2::new(self.paddle.length_scale, 1.)
+        } else {
+            na::Vector2::new(1., self.paddle.length_scale)
+        };
+        let draw_param = graphics::DrawParam::default()
+            .dest(self.paddle.get_position())
+            .scale(scale)
+            .color(self.color);
+
+        graphics::draw(ctx, self.paddle.get_mesh(), draw_param).unwrap();
     }
 
     pub fn get_name(&self) -> &str {
         self.name.as_str()
     }
+
+    pub fn get_color(&self) -> graphics::Color {
+        self.color
+    }
+
+    pub fn get_controls(&self) -> &Controls {
+        &self.controls
+    }
 }
 
 pub struct Paddle {
     position: na::Point2<f32>,
     pub size: (f32, f32),
+    // Set for a paddle wider than it's tall, which moves left and right
+    horizontal: bool,
+    // Grow and shrink power-ups stretch the paddle from its normal length
+    base_length: f32,
+    length_scale: f32,
+    // The paddle stays between the start of its track and this far along
+    track_length: f32,
+    // Set for a mallet, which goes anywhere in this area instead of along a track
+    area: Option<graphics::Rect>,
+    // How fast the paddle moved in the last update, a mallet passes it on to the puck
+    pub velocity: na::Vector2<f32>,
     mesh: graphics::Mesh,
     speed: f32,
 }
@@ -102,29 +338,174 @@ impl Paddle {
             graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, graphics::WHITE)
                 .unwrap();
 
+        let horizontal = rect.w > rect.h;
         Self {
             position: na::Point2::new(coord.x, coord.y),
             size: (rect.w, rect.h),
+            horizontal,
+            base_length: rect.w.max(rect.h),
+            length_scale: 1.,
+            track_length: ARENA_HEIGHT,
+            area: None,
+            velocity: na::Vector2::new(0., 0.),
             mesh,
             speed,
         }
     }
 
-    fn move_direction(&mut self, ctx: &mut Context, direction: Direction, dt: f32) {
+    pub fn mallet(
+        ctx: &mut Context,
+        coord: na::Vector2<f32>,
+        radius: f32,
+        speed: f32,
+        area: graphics::Rect,
+    ) -> Self {
+        let size = radius * 2.;
+        let rect = graphics::Rect::new(-radius, -radius, size, size);
+        let mut mallet = Self::new(ctx, coord, rect, speed);
+        mallet.mesh = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            na::Point2::new(0., 0.),
+            radius,
+            0.5,
+            graphics::WHITE,
+        )
+        .unwrap();
+        mallet.area = Some(area);
+        mallet.clamp_to_arena();
+        mallet
+    }
+
+    // Radius of a mallet, None for a flat paddle
+    pub fn mallet_radius(&self) -> Option<f32> {
+        self.area.map(|_| self.size.0 * 0.5)
+    }
+
+    // Position along the track, y for an upright paddle and x for a horizontal one
+    fn along(&self) -> f32 {
+        if self.horizontal {
+            self.position.x
+        } else {
+            self.position.y
+        }
+    }
+
+    fn along_mut(&mut self) -> &mut f32 {
+        if self.horizontal {
+            &mut self.position.x
+        } else {
+            &mut self.position.y
+        }
+    }
+
+    fn length(&self) -> f32 {
+        if self.horizontal {
+            self.size.0
+        } else {
+            self.size.1
+        }
+    }
+
+    fn move_direction(&mut self, direction: Direction, dt: f32) {
+        let step = self.speed * dt;
         match direction {
-            Direction::Up => self.position.y -= self.speed * dt,
-            Direction::Down => self.position.y += self.speed * dt,
+            Direction::Up => *self.along_mut() -= step,
+            Direction::Down => *self.along_mut() += step,
         };
 
-        let scr_height = graphics::drawable_size(ctx).1;
-        let height_half = self.size.1 * 0.5;
-        Util::clamp(&mut self.position.y, height_half, scr_height - height_half);
+        self.clamp_to_arena();
+    }
+
+    // Move towards `target` along the track, but never faster than the paddle speed
+    fn move_towards(&mut self, target: f32, dt: f32) {
+        let max_step = self.speed * dt;
+        let mut step = target - self.along();
+        Util::clamp(&mut step, -max_step, max_step);
+        *self.along_mut() += step;
+
+        self.clamp_to_arena();
+    }
+
+    // Move a mallet the way `direction` points, at full speed whatever its length
+    fn move_free(&mut self, direction: na::Vector2<f32>, dt: f32) {
+        let length = direction.norm();
+        if length > 0. {
+            self.position += direction / length.max(1.) * self.speed * dt;
+        }
+        self.clamp_to_arena();
+    }
+
+    // Move a mallet towards `target`, but never faster than its speed
+    fn move_to(&mut self, target: na::Point2<f32>, dt: f32) {
+        let max_step = self.speed * dt;
+        let step = target - self.position;
+        let distance = step.norm();
+        if distance > max_step {
+            self.position += step * (max_step / distance);
+        } else {
+            self.position = target;
+        }
+        self.clamp_to_arena();
+    }
+
+    fn clamp_to_arena(&mut self) {
+        // A mallet stays entirely inside its area
+        if let Some(area) = self.area {
+            let (width_half, height_half) = (self.size.0 * 0.5, self.size.1 * 0.5);
+            Util::clamp(
+                &mut self.position.x,
+                area.x + width_half,
+                area.right() - width_half,
+            );
+            Util::clamp(
+                &mut self.position.y,
+                area.y + height_half,
+                area.bottom() - height_half,
+            );
+            return;
+        }
+
+        let length_half = self.length() * 0.5;
+        let track_length = self.track_length;
+        Util::clamp(self.along_mut(), length_half, track_length - length_half);
     }
 
     pub fn get_mesh(&self) -> &graphics::Mesh {
         &self.mesh
     }
 
+    pub fn set_track_length(&mut self, length: f32) {
+        self.track_length = length;
+        self.clamp_to_arena();
+    }
+
+    pub fn set_length_scale(&mut self, scale: f32) {
+        self.length_scale = scale;
+        let length = self.base_length * scale;
+        if self.horizontal {
+            self.size.0 = length;
+        } else {
+            self.size.1 = length;
+        }
+        self.clamp_to_arena();
+    }
+
+    // Where the other object hits along the paddle, from -1.0 (top or left) to
+    // 1.0 (bottom or right)
+    pub fn hit_offset(&self, other: &dyn Collidable) -> f32 {
+        let length_half = self.length() * 0.5;
+        let distance = other.get_position() - self.position;
+        let along = if self.horizontal {
+            distance.x
+        } else {
+            distance.y
+        };
+        let mut offset = along / length_half;
+        Util::clamp(&mut offset, -1., 1.);
+        offset
+    }
+
     pub fn set_position(&mut self, position: na::Point2<f32>) {
         self.position = position;
     }
@@ -0,0 +1,374 @@
+use rand::{thread_rng, Rng};
+
+use crate::{
+    arena::Arena, collidable::Collidable, graphics, na, settings::Settings, Context, DrawParam,
+    Text,
+};
+
+// Seconds between two items appearing
+const SPAWN_INTERVAL: f32 = 6.;
+// Most items waiting in the arena at once
+const MAX_ITEMS: usize = 2;
+const ITEM_SIZE: f32 = 32.;
+const ITEM_SIZE_HALF: f32 = ITEM_SIZE * 0.5;
+// Items appear in the middle part of the arena, away from the paddles
+const SPAWN_AREA_WIDTH: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PowerUpKind {
+    Grow,
+    Shrink,
+    FastBall,
+    SlowBall,
+    MultiBall,
+    // The paddle catches the ball and lets go a moment later
+    Sticky,
+    Reverse,
+    // A wall behind the paddle keeps the ball in play
+    Shield,
+}
+
+impl PowerUpKind {
+    // Length of every per-kind table, e.g. the spawn weights
+    pub const COUNT: usize = 8;
+    pub const ALL: [PowerUpKind; Self::COUNT] = [
+        PowerUpKind::Grow,
+        PowerUpKind::Shrink,
+        PowerUpKind::FastBall,
+        PowerUpKind::SlowBall,
+        PowerUpKind::MultiBall,
+        PowerUpKind::Sticky,
+        PowerUpKind::Reverse,
+        PowerUpKind::Shield,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PowerUpKind::Grow => "Grow paddle",
+            PowerUpKind::Shrink => "Shrink paddle",
+            PowerUpKind::FastBall => "Fast ball",
+            PowerUpKind::SlowBall => "Slow ball",
+            PowerUpKind::MultiBall => "Multi-ball",
+            PowerUpKind::Sticky => "Sticky paddle",
+            PowerUpKind::Reverse => "Reversed controls",
+            PowerUpKind::Shield => "Shield wall",
+        }
+    }
+
+    // Letter drawn on the item
+    fn symbol(self) -> &'static str {
+        match self {
+            PowerUpKind::Grow => "G",
+            PowerUpKind::Shrink => "S",
+            PowerUpKind::FastBall => "F",
+            PowerUpKind::SlowBall => "L",
+            PowerUpKind::MultiBall => "M",
+            PowerUpKind::Sticky => "C",
+            PowerUpKind::Reverse => "R",
+            PowerUpKind::Shield => "W",
+        }
+    }
+
+    pub fn color(self) -> graphics::Color {
+        match self {
+            PowerUpKind::Grow => graphics::Color::from_rgb(96, 192, 96),
+            PowerUpKind::Shrink => graphics::Color::from_rgb(192, 80, 80),
+            PowerUpKind::FastBall => graphics::Color::from_rgb(224, 160, 48),
+            PowerUpKind::SlowBall => graphics::Color::from_rgb(80, 160, 224),
+            PowerUpKind::MultiBall => graphics::Color::from_rgb(224, 224, 96),
+            PowerUpKind::Sticky => graphics::Color::from_rgb(176, 96, 208),
+            PowerUpKind::Reverse => graphics::Color::from_rgb(224, 96, 160),
+            PowerUpKind::Shield => graphics::Color::from_rgb(192, 192, 192),
+        }
+    }
+
+    // Seconds the effect lasts
+    pub fn duration(self) -> f32 {
+        match self {
+            PowerUpKind::Grow | PowerUpKind::Sticky => 10.,
+            PowerUpKind::Shrink | PowerUpKind::MultiBall | PowerUpKind::Shield => 8.,
+            PowerUpKind::FastBall | PowerUpKind::SlowBall | PowerUpKind::Reverse => 6.,
+        }
+    }
+
+    pub fn default_weight(self) -> i32 {
+        match self {
+            PowerUpKind::MultiBall | PowerUpKind::Reverse => 2,
+            _ => 3,
+        }
+    }
+
+    // Shrink and reverse are played on the opponents of whoever collects them
+    fn affects_opponent(self) -> bool {
+        matches!(self, PowerUpKind::Shrink | PowerUpKind::Reverse)
+    }
+
+    pub fn index(self) -> usize {
+        PowerUpKind::ALL
+            .iter()
+            .position(|kind| *kind == self)
+            .unwrap_or(0)
+    }
+}
+
+// An item waiting to be collected
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    pub position: na::Point2<f32>,
+}
+
+impl Collidable for PowerUp {
+    fn get_position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    fn get_size(&self) -> (f32, f32) {
+        (ITEM_SIZE, ITEM_SIZE)
+    }
+}
+
+// A collected item running out, `player` is the one it acts on
+pub struct ActiveEffect {
+    pub kind: PowerUpKind,
+    pub player: usize,
+    pub remaining: f32,
+}
+
+// Items in the arena and the effects of the ones already collected
+pub struct PowerUps {
+    items: Vec<PowerUp>,
+    effects: Vec<ActiveEffect>,
+    spawn_timer: f32,
+    mesh: graphics::Mesh,
+}
+
+impl PowerUps {
+    pub fn new(ctx: &mut Context) -> Self {
+        let mesh = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            na::Point2::new(0., 0.),
+            ITEM_SIZE_HALF,
+            0.5,
+            graphics::WHITE,
+        )
+        .unwrap();
+
+        Self {
+            items: Vec::new(),
+            effects: Vec::new(),
+            spawn_timer: 0.,
+            mesh,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.items.clear();
+        self.effects.clear();
+        self.spawn_timer = 0.;
+    }
+
+    // Runs the effects down, items only spawn on their own with `spawns`
+    pub fn update(&mut self, settings: &Settings, arena: &Arena, dt: f32, spawns: bool) {
+        for effect in &mut self.effects {
+            effect.remaining -= dt;
+        }
+        self.effects.retain(|effect| effect.remaining > 0.);
+
+        if !settings.power_ups {
+            self.items.clear();
+            return;
+        }
+        if !spawns {
+            return;
+        }
+
+        self.spawn_timer += dt;
+        if self.spawn_timer < SPAWN_INTERVAL {
+            return;
+        }
+        self.spawn_timer = 0.;
+
+        if self.items.len() < MAX_ITEMS {
+            if let Some(kind) = Self::pick(settings) {
+                let mut rng = thread_rng();
+                let half_width = arena.width * SPAWN_AREA_WIDTH * 0.5;
+                let position = na::Point2::new(
+                    arena.width * 0.5 + rng.gen_range(-half_width, half_width),
+                    rng.gen_range(ITEM_SIZE * 2., arena.height - ITEM_SIZE * 2.),
+                );
+                self.items.push(PowerUp { kind, position });
+            }
+        }
+    }
+
+    // Leave a random item where something broke, e.g. a breakout brick
+    pub fn drop_at(&mut self, settings: &Settings, position: na::Point2<f32>) {
+        if !settings.power_ups || self.items.len() >= MAX_ITEMS {
+            return;
+        }
+        if let Some(kind) = Self::pick(settings) {
+            self.items.push(PowerUp { kind, position });
+        }
+    }
+
+    // Random kind, more likely the higher its spawn weight
+    fn pick(settings: &Settings) -> Option<PowerUpKind> {
+        let total: i32 = settings.power_up_weights.iter().sum();
+        if total <= 0 {
+            return None;
+        }
+
+        let mut roll = thread_rng().gen_range(0, total);
+        for kind in PowerUpKind::ALL.iter() {
+            let weight = settings.power_up_weights[kind.index()];
+            if roll < weight {
+                return Some(*kind);
+            }
+            roll -= weight;
+        }
+        None
+    }
+
+    // Remove and return the item the ball touches, if any
+    pub fn take(&mut self, ball: &dyn Collidable) -> Option<PowerUp> {
+        let index = self
+            .items
+            .iter()
+            .position(|item| item.check_collision(ball))?;
+        Some(self.items.remove(index))
+    }
+
+    // Start the effect of an item collected by `collector`, items against the
+    // opponents act on all of them. Picking the same item up again starts its timer over.
+    pub fn activate(&mut self, kind: PowerUpKind, collector: usize, opponents: &[usize]) {
+        let targets: Vec<usize> = if kind.affects_opponent() {
+            opponents.to_vec()
+        } else {
+            vec![collector]
+        };
+
+        for player in targets {
+            self.effects
+                .retain(|effect| !(effect.kind == kind && effect.player == player));
+            self.effects.push(ActiveEffect {
+                kind,
+                player,
+                remaining: kind.duration(),
+            });
+        }
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind, player: usize) -> bool {
+        self.effects
+            .iter()
+            .any(|effect| effect.kind == kind && effect.player == player)
+    }
+
+    // Effects acting on the given player, for the HUD
+    pub fn effects(&self, player: usize) -> impl Iterator<Item = &ActiveEffect> {
+        self.effects
+            .iter()
+            .filter(move |effect| effect.player == player)
+    }
+
+    // Paddle height relative to normal
+    pub fn paddle_scale(&self, player: usize) -> f32 {
+        let mut scale = 1.;
+        if self.is_active(PowerUpKind::Grow, player) {
+            scale *= 1.5;
+        }
+        if self.is_active(PowerUpKind::Shrink, player) {
+            scale *= 0.6;
+        }
+        scale
+    }
+
+    // Ball speed relative to normal, fast and slow ball affect every ball in play
+    pub fn ball_speed_factor(&self) -> f32 {
+        self.effects
+            .iter()
+            .fold(1., |factor, effect| match effect.kind {
+                PowerUpKind::FastBall => factor * 1.4,
+                PowerUpKind::SlowBall => factor * 0.65,
+                _ => factor,
+            })
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        for item in &self.items {
+            graphics::draw(
+                ctx,
+                &self.mesh,
+                DrawParam::default()
+                    .dest(item.position)
+                    .color(item.kind.color()),
+            )
+            .unwrap();
+
+            let symbol = Text::new(item.kind.symbol());
+            let symbol_position = na::Point2::new(
+                item.position.x - symbol.width(ctx) as f32 * 0.5,
+                item.position.y - symbol.height(ctx) as f32 * 0.5,
+            );
+            graphics::draw(
+                ctx,
+                &symbol,
+                DrawParam::default()
+                    .dest(symbol_position)
+                    .color(graphics::BLACK),
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(weights: [i32; PowerUpKind::COUNT]) -> Settings {
+        let mut settings = Settings::new();
+        settings.power_up_weights = weights;
+        settings
+    }
+
+    #[test]
+    fn pick_nothing_without_weights() {
+        assert_eq!(PowerUps::pick(&settings([0; PowerUpKind::COUNT])), None);
+    }
+
+    #[test]
+    fn pick_only_weighted_kinds() {
+        let mut weights = [0; PowerUpKind::COUNT];
+        weights[PowerUpKind::Sticky.index()] = 2;
+        weights[PowerUpKind::Shield.index()] = 1;
+        let settings = settings(weights);
+        for _ in 0..200 {
+            let kind = PowerUps::pick(&settings).unwrap();
+            assert!(kind == PowerUpKind::Sticky || kind == PowerUpKind::Shield);
+        }
+    }
+
+    #[test]
+    fn pick_follows_the_weights() {
+        let mut weights = [0; PowerUpKind::COUNT];
+        weights[PowerUpKind::Grow.index()] = 1;
+        weights[PowerUpKind::Shrink.index()] = 3;
+        let settings = settings(weights);
+        let grown = (0..4000)
+            .filter(|_| PowerUps::pick(&settings) == Some(PowerUpKind::Grow))
+            .count();
+        // A quarter of the picks, far outside these bounds only by a fluke
+        assert!((800..1200).contains(&grown), "{}", grown);
+    }
+
+    #[test]
+    fn every_kind_can_be_picked() {
+        let settings = Settings::new();
+        for kind in PowerUpKind::ALL.iter() {
+            assert!(settings.power_up_weights[kind.index()] > 0);
+            assert!((0..2000).any(|_| PowerUps::pick(&settings) == Some(*kind)));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{graphics, side::Side, storage::Storage, Context, ControlMode};
+
+const PROFILES_PATH: &str = "/profiles.toml";
+// Enough profiles to seat a player on every edge
+const MIN_PROFILES: usize = Side::ALL.len();
+pub const MAX_NAME_LENGTH: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PaddleColor {
+    White,
+    Orange,
+    Red,
+    Green,
+    Blue,
+    Yellow,
+}
+
+impl PaddleColor {
+    pub const ALL: [PaddleColor; 6] = [
+        PaddleColor::White,
+        PaddleColor::Orange,
+        PaddleColor::Red,
+        PaddleColor::Green,
+        PaddleColor::Blue,
+        PaddleColor::Yellow,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PaddleColor::White => "White",
+            PaddleColor::Orange => "Orange",
+            PaddleColor::Red => "Red",
+            PaddleColor::Green => "Green",
+            PaddleColor::Blue => "Blue",
+            PaddleColor::Yellow => "Yellow",
+        }
+    }
+
+    pub fn to_color(self) -> graphics::Color {
+        match self {
+            PaddleColor::White => graphics::WHITE,
+            PaddleColor::Orange => graphics::Color::from_rgb(192, 128, 64),
+            PaddleColor::Red => graphics::Color::from_rgb(220, 70, 70),
+            PaddleColor::Green => graphics::Color::from_rgb(90, 200, 100),
+            PaddleColor::Blue => graphics::Color::from_rgb(90, 140, 230),
+            PaddleColor::Yellow => graphics::Color::from_rgb(230, 210, 80),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub controls: ControlMode,
+    pub color: PaddleColor,
+}
+
+impl Profile {
+    pub fn new(name: &str, color: PaddleColor) -> Self {
+        Self {
+            name: String::from(name),
+            controls: ControlMode::Keyboard,
+            color,
+        }
+    }
+}
+
+// Local profiles, persisted in the user config directory
+#[derive(Default, Serialize, Deserialize)]
+pub struct ProfileStore {
+    pub profiles: Vec<Profile>,
+}
+
+impl ProfileStore {
+    pub fn load(ctx: &mut Context) -> Self {
+        let mut store: Self = Storage::load(ctx, PROFILES_PATH);
+        store.fill_up();
+        store
+    }
+
+    // Every player in a match needs a profile to choose from, add "Player N"
+    // profiles under names nobody has taken yet
+    fn fill_up(&mut self) {
+        let mut number = 1;
+        while self.profiles.len() < MIN_PROFILES {
+            let name = format!("Player {}", number);
+            number += 1;
+            if self.find(&name).is_none() {
+                let color = PaddleColor::ALL[self.profiles.len() % PaddleColor::ALL.len()];
+                self.profiles.push(Profile::new(&name, color));
+            }
+        }
+    }
+
+    pub fn save(&self, ctx: &mut Context) {
+        if let Err(error) = Storage::save(ctx, PROFILES_PATH, self) {
+            eprintln!("Could not save profiles: {}", error);
+        }
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.profiles
+            .iter()
+            .position(|profile| profile.name.eq_ignore_ascii_case(name))
+    }
+
+    // Returns the index of the profile with the given name, creating it if needed
+    pub fn find_or_create(&mut self, name: &str) -> usize {
+        if let Some(index) = self.find(name) {
+            return index;
+        }
+
+        let color = PaddleColor::ALL[self.profiles.len() % PaddleColor::ALL.len()];
+        self.profiles.push(Profile::new(name, color));
+        self.profiles.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(names: &[&str]) -> ProfileStore {
+        ProfileStore {
+            profiles: names
+                .iter()
+                .map(|name| Profile::new(name, PaddleColor::White))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn fill_up_skips_taken_names() {
+        let mut store = store(&["Alice", "Player 3"]);
+        store.fill_up();
+        let names: Vec<&str> = store.profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Alice", "Player 3", "Player 1", "Player 2"]);
+    }
+
+    #[test]
+    fn fill_up_leaves_full_stores_alone() {
+        let mut store = store(&["player 1", "Player 2", "Bob", "Carol", "Dave"]);
+        store.fill_up();
+        assert_eq!(store.profiles.len(), 5);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    graphics,
+    menu::MenuScene as ms,
+    na,
+    viewport::{ARENA_HEIGHT, ARENA_WIDTH},
+    Context, KeyCode, MouseButton, Scene, World,
+};
+
+const SCROLL_SPEED: f32 = 40.;
+const MANUAL_SCROLL_STEP: f32 = 50.;
+const LINE_SPACING: f32 = 10.;
+
+enum Line {
+    Heading(String),
+    Body(String),
+    Blank,
+}
+
+pub struct AboutScene {
+    lines: Vec<graphics::Text>,
+    content_height: f32,
+    scroll_offset: f32,
+
+    is_done: bool,
+}
+
+impl AboutScene {
+    pub fn new(ctx: &mut Context, world: &World) -> Self {
+        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
+        let title_color = graphics::Color::from((192, 128, 64, 255));
+        let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
+
+        let lines: Vec<graphics::Text> = Self::content(world)
+            .into_iter()
+            .map(|line| {
+                let fragment = match line {
+                    Line::Heading(text) => graphics::TextFragment::new(text)
+                        .color(title_color)
+                        .scale(graphics::Scale::uniform(36.0)),
+                    Line::Body(text) => graphics::TextFragment::new(text)
+                        .color(silver)
+                        .scale(graphics::Scale::uniform(22.0)),
+                    Line::Blank => {
+                        graphics::TextFragment::new(" ").scale(graphics::Scale::uniform(22.0))
+                    }
+                };
+                graphics::Text::new(fragment.font(font))
+            })
+            .collect();
+
+        let content_height = lines
+            .iter()
+            .map(|line| line.height(ctx) as f32 + LINE_SPACING)
+            .sum();
+
+        Self {
+            lines,
+            content_height,
+            scroll_offset: 0.,
+            is_done: false,
+        }
+    }
+
+    fn content(world: &World) -> Vec<Line> {
+        let heading = |text: &str| Line::Heading(String::from(text));
+        let body = |text: &str| Line::Body(String::from(text));
+
+        let mut lines = vec![
+            heading("Pong-A-Long"),
+            Line::Body(format!("Version {}", env!("CARGO_PKG_VERSION"))),
+            Line::Blank,
+            body("Atari's 1972 classic pong, made with rust."),
+            body("The project is first and foremost about learning Rust,"),
+            body("and about getting my hands dirty with game development."),
+            Line::Blank,
+            heading("Controls"),
+        ];
+        lines.extend(world.players.iter().map(|player| {
+            Line::Body(format!(
+                "{}: {}",
+                player.get_name(),
+                player.get_controls().describe()
+            ))
+        }));
+        lines.extend(vec![
+            body("Gamepads: left stick or d-pad, set a profile's controls to Gamepad"),
+            body("Menus: Up / Down or W / S to move, Return or Space to select"),
+            body("Left / Right or A / D to change a value, Backspace to go back"),
+            body("P: pause the match, Escape: quit the game"),
+            Line::Blank,
+            heading("Engine"),
+            body("Made with ggez - https://ggez.rs"),
+            body("Written in Rust - https://www.rust-lang.org"),
+            Line::Blank,
+            heading("Font"),
+            body("DejaVu Serif - https://dejavu-fonts.github.io"),
+            body("Copyright (c) 2003 by Bitstream, Inc. All Rights Reserved."),
+            body("Bitstream Vera is a trademark of Bitstream, Inc."),
+            body("DejaVu changes are in public domain."),
+            body("Distributed under the Bitstream Vera Fonts license."),
+            Line::Blank,
+            heading("Author"),
+            body("Troligtvis - https://github.com/troligtvis/pong"),
+        ]);
+        lines
+    }
+
+    // Keep the offset within one full pass of the content, the credits loop
+    fn wrap_scroll_offset(&mut self, scr_height: f32) {
+        let loop_length = self.content_height + scr_height;
+        self.scroll_offset = self.scroll_offset.rem_euclid(loop_length);
+    }
+}
+
+impl Scene<World> for AboutScene {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
+        if self.is_done {
+            return Some(Box::new(ms::new(ctx, world)));
+        }
+
+        let scr_height = ARENA_HEIGHT;
+        self.scroll_offset += SCROLL_SPEED * world.get_dt();
+        self.wrap_scroll_offset(scr_height);
+
+        None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _world: &mut World) {
+        let (scr_width, scr_height) = (ARENA_WIDTH, ARENA_HEIGHT);
+
+        // Content starts below the bottom edge and scrolls upwards
+        let mut y = scr_height - self.scroll_offset;
+        for line in &self.lines {
+            let width = line.width(ctx) as f32;
+            let height = line.height(ctx) as f32;
+
+            if y + height > 0. && y < scr_height {
+                let dest = na::Point2::new((scr_width / 2.0) - (width / 2.0), y);
+                graphics::draw(ctx, line, graphics::DrawParam::new().dest(dest)).unwrap();
+            }
+
+            y += height + LINE_SPACING;
+        }
+    }
+
+    fn input(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut World,
+        keycode: KeyCode,
+        _pressed: bool,
+        _repeat: bool,
+    ) {
+        let scr_height = ARENA_HEIGHT;
+
+        match keycode {
+            KeyCode::Back | KeyCode::Return | KeyCode::Space => self.is_done = true,
+            KeyCode::Up | KeyCode::W => {
+                self.scroll_offset -= MANUAL_SCROLL_STEP;
+                self.wrap_scroll_offset(scr_height);
+            }
+            KeyCode::Down | KeyCode::S => {
+                self.scroll_offset += MANUAL_SCROLL_STEP;
+                self.wrap_scroll_offset(scr_height);
+            }
+            _ => {}
+        };
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut World,
+        button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) {
+        if button == MouseButton::Right {
+            self.is_done = true;
+        }
+    }
+
+    fn name(&self) -> &str {
+        "About Scene"
+    }
+}
@@ -0,0 +1,708 @@
+use crate::{
+    arena::{Arena, Obstacle},
+    game::GameScene as gs,
+    graphics,
+    menu::MenuScene as ms,
+    mode::GameMode,
+    na,
+    player::{PADDLE_HEIGHT, PADDLE_WIDTH},
+    side::Side,
+    ui::text_input::{TextInput, TextInputEvent},
+    Context, DrawParam, KeyCode, MouseButton, Scene, Text, World,
+};
+
+// Spacing of the grid positions snap to
+const GRID_SIZE: f32 = 20.;
+// Size of a freshly placed obstacle
+const NEW_OBSTACLE_SIZE: f32 = 40.;
+const MIN_OBSTACLE_SIZE: f32 = 10.;
+// Goal openings can't be made smaller than this
+const MIN_GOAL_HEIGHT: f32 = 60.;
+// How far a newly moving obstacle travels and how fast
+const NEW_PATH_LENGTH: f32 = 160.;
+const NEW_PATH_SPEED: f32 = 120.;
+const SPEED_STEP: f32 = 20.;
+// Side of the square grabbed to resize an obstacle or move a goal post
+const HANDLE_SIZE: f32 = 12.;
+// Oldest edits are forgotten past this
+const MAX_UNDO: usize = 100;
+const MAX_NAME_LENGTH: usize = 24;
+// Arena files hold the goals and paddle spawns on the left and right end
+const END_SIDES: [Side; 2] = [Side::Left, Side::Right];
+
+// Something the mouse is dragging
+#[derive(Clone, Copy, PartialEq)]
+enum Handle {
+    Obstacle(usize),
+    // Bottom right corner of an obstacle, the top left stays put
+    Resize(usize),
+    // Far end of a moving obstacle's path
+    PathEnd(usize),
+    // Top of a goal opening, the opening stays centred
+    Goal(usize),
+    Spawn(usize),
+}
+
+struct Drag {
+    handle: Handle,
+    // From the mouse to the centre of what's dragged
+    grab_offset: na::Vector2<f32>,
+    // The arena before the drag started, kept for undo
+    before: Arena,
+}
+
+// The arena being edited with its undo history, kept while test-playing
+pub struct EditorSession {
+    arena: Arena,
+    undo: Vec<Arena>,
+    redo: Vec<Arena>,
+}
+
+impl EditorSession {
+    fn new(arena: Arena) -> Self {
+        Self {
+            arena,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+// Place, resize and move obstacles, goals and paddle spawns with the mouse
+pub struct EditorScene {
+    session: EditorSession,
+    // Arenas that can be opened for editing
+    arenas: Vec<Arena>,
+    opened: usize,
+
+    selected: Option<usize>,
+    drag: Option<Drag>,
+    snap: bool,
+    // Set while typing a name to save under
+    name_input: Option<TextInput>,
+    status: String,
+
+    is_done: bool,
+    is_testing: bool,
+}
+
+impl EditorScene {
+    pub fn new(ctx: &mut Context) -> Self {
+        let arena = Arena {
+            name: String::from("Untitled"),
+            ..Arena::classic()
+        };
+        Self::resume(ctx, EditorSession::new(arena))
+    }
+
+    // Back from test-playing with the history intact
+    pub fn resume(ctx: &mut Context, session: EditorSession) -> Self {
+        Self::with_arenas(session, Arena::load_all(ctx))
+    }
+
+    fn with_arenas(session: EditorSession, arenas: Vec<Arena>) -> Self {
+        Self {
+            session,
+            arenas,
+            opened: 0,
+            selected: None,
+            drag: None,
+            snap: true,
+            name_input: None,
+            status: String::new(),
+            is_done: false,
+            is_testing: false,
+        }
+    }
+
+    fn arena(&self) -> &Arena {
+        &self.session.arena
+    }
+
+    fn snap_point(&self, point: na::Point2<f32>) -> na::Point2<f32> {
+        if self.snap {
+            na::Point2::new(
+                (point.x / GRID_SIZE).round() * GRID_SIZE,
+                (point.y / GRID_SIZE).round() * GRID_SIZE,
+            )
+        } else {
+            point
+        }
+    }
+
+    // Remember the arena as it is before an edit
+    fn record(&mut self, before: Arena) {
+        let session = &mut self.session;
+        session.undo.push(before);
+        if session.undo.len() > MAX_UNDO {
+            session.undo.remove(0);
+        }
+        session.redo.clear();
+    }
+
+    // Apply an edit that can be undone. A drag in progress ends first, the edit
+    // could remove what it holds on to.
+    fn edit<F: FnOnce(&mut Arena)>(&mut self, change: F) {
+        self.end_drag();
+        let before = self.session.arena.clone();
+        change(&mut self.session.arena);
+        if self.session.arena != before {
+            self.record(before);
+        }
+    }
+
+    fn undo(&mut self) {
+        self.end_drag();
+        let session = &mut self.session;
+        if let Some(arena) = session.undo.pop() {
+            let current = std::mem::replace(&mut session.arena, arena);
+            session.redo.push(current);
+            self.selected = None;
+        }
+    }
+
+    fn redo(&mut self) {
+        self.end_drag();
+        let session = &mut self.session;
+        if let Some(arena) = session.redo.pop() {
+            let current = std::mem::replace(&mut session.arena, arena);
+            session.undo.push(current);
+            self.selected = None;
+        }
+    }
+
+    fn handle_rect(center: na::Point2<f32>) -> graphics::Rect {
+        graphics::Rect::new(
+            center.x - HANDLE_SIZE * 0.5,
+            center.y - HANDLE_SIZE * 0.5,
+            HANDLE_SIZE,
+            HANDLE_SIZE,
+        )
+    }
+
+    fn obstacle_rect(obstacle: &Obstacle) -> graphics::Rect {
+        graphics::Rect::new(
+            obstacle.x - obstacle.width * 0.5,
+            obstacle.y - obstacle.height * 0.5,
+            obstacle.width,
+            obstacle.height,
+        )
+    }
+
+    fn resize_handle(obstacle: &Obstacle) -> na::Point2<f32> {
+        na::Point2::new(
+            obstacle.x + obstacle.width * 0.5,
+            obstacle.y + obstacle.height * 0.5,
+        )
+    }
+
+    fn goal_handle(&self, side: usize) -> na::Point2<f32> {
+        let arena = self.arena();
+        let x = if side == 0 { 0. } else { arena.width };
+        na::Point2::new(x, arena.goal_range(END_SIDES[side]).0)
+    }
+
+    fn spawn_rect(&self, side: usize) -> graphics::Rect {
+        let start = self.arena().paddle_start(END_SIDES[side]);
+        graphics::Rect::new(
+            start.x - PADDLE_WIDTH * 0.5,
+            start.y - PADDLE_HEIGHT * 0.5,
+            PADDLE_WIDTH,
+            PADDLE_HEIGHT,
+        )
+    }
+
+    // What's under the mouse, handles of the selected obstacle come first and
+    // obstacles placed later sit on top of earlier ones
+    fn handle_at(&self, point: na::Point2<f32>) -> Option<(Handle, na::Point2<f32>)> {
+        let arena = self.arena();
+        if let Some(index) = self.selected {
+            let obstacle = &arena.obstacles[index];
+            let corner = Self::resize_handle(obstacle);
+            if Self::handle_rect(corner).contains(point) {
+                return Some((Handle::Resize(index), corner));
+            }
+            if let Some([x, y]) = obstacle.moves_to {
+                let end = na::Point2::new(x, y);
+                if Self::handle_rect(end).contains(point) {
+                    return Some((Handle::PathEnd(index), end));
+                }
+            }
+        }
+
+        for (index, side) in END_SIDES.iter().enumerate() {
+            let goal = self.goal_handle(index);
+            if Self::handle_rect(goal).contains(point) {
+                return Some((Handle::Goal(index), goal));
+            }
+            if self.spawn_rect(index).contains(point) {
+                return Some((Handle::Spawn(index), arena.paddle_start(*side)));
+            }
+        }
+
+        arena
+            .obstacles
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, obstacle)| Self::obstacle_rect(obstacle).contains(point))
+            .map(|(index, obstacle)| {
+                (
+                    Handle::Obstacle(index),
+                    na::Point2::new(obstacle.x, obstacle.y),
+                )
+            })
+    }
+
+    fn start_drag(&mut self, handle: Handle, grab_offset: na::Vector2<f32>) {
+        self.drag = Some(Drag {
+            handle,
+            grab_offset,
+            before: self.arena().clone(),
+        });
+    }
+
+    fn drag_motion(&mut self, point: na::Point2<f32>) {
+        if let Some(drag) = &self.drag {
+            let (handle, offset) = (drag.handle, drag.grab_offset);
+            self.drag_to(handle, point + offset);
+        }
+    }
+
+    // A whole drag is undone in one go
+    fn end_drag(&mut self) {
+        if let Some(drag) = self.drag.take() {
+            if *self.arena() != drag.before {
+                self.record(drag.before);
+            }
+        }
+    }
+
+    // Move what's being dragged so its centre ends up at `point`
+    fn drag_to(&mut self, handle: Handle, point: na::Point2<f32>) {
+        let point = self.snap_point(point);
+        let arena = &mut self.session.arena;
+        let point = na::Point2::new(
+            point.x.max(0.).min(arena.width),
+            point.y.max(0.).min(arena.height),
+        );
+
+        match handle {
+            Handle::Obstacle(index) => {
+                let obstacle = &mut arena.obstacles[index];
+                let (dx, dy) = (point.x - obstacle.x, point.y - obstacle.y);
+                obstacle.x = point.x;
+                obstacle.y = point.y;
+                // The path moves along with the obstacle
+                if let Some([x, y]) = obstacle.moves_to {
+                    obstacle.moves_to = Some([x + dx, y + dy]);
+                }
+            }
+            Handle::Resize(index) => {
+                let obstacle = &mut arena.obstacles[index];
+                let left = obstacle.x - obstacle.width * 0.5;
+                let top = obstacle.y - obstacle.height * 0.5;
+                obstacle.width = (point.x - left).max(MIN_OBSTACLE_SIZE);
+                obstacle.height = (point.y - top).max(MIN_OBSTACLE_SIZE);
+                obstacle.x = left + obstacle.width * 0.5;
+                obstacle.y = top + obstacle.height * 0.5;
+            }
+            Handle::PathEnd(index) => {
+                arena.obstacles[index].moves_to = Some([point.x, point.y]);
+            }
+            Handle::Goal(side) => {
+                let height = ((arena.height * 0.5 - point.y) * 2.)
+                    .max(MIN_GOAL_HEIGHT)
+                    .min(arena.height);
+                let mut heights = [0.; 2];
+                for (index, end) in END_SIDES.iter().enumerate() {
+                    let (start, end) = arena.goal_range(*end);
+                    heights[index] = end - start;
+                }
+                heights[side] = height;
+                // Goals as tall as the arena are the default
+                arena.goal_heights = if heights.iter().all(|h| *h >= arena.height) {
+                    None
+                } else {
+                    Some(heights)
+                };
+            }
+            Handle::Spawn(side) => {
+                let mut starts = [[0.; 2]; 2];
+                for (index, start) in starts.iter_mut().enumerate() {
+                    let position = arena.paddle_start(END_SIDES[index]);
+                    *start = [position.x, position.y];
+                }
+                starts[side] = [point.x, point.y];
+                arena.paddle_starts = Some(starts);
+            }
+        }
+    }
+
+    fn place_obstacle(&mut self, point: na::Point2<f32>) {
+        let point = self.snap_point(point);
+        self.edit(|arena| {
+            let obstacle = Obstacle::new(point.x, point.y, NEW_OBSTACLE_SIZE, NEW_OBSTACLE_SIZE);
+            arena.obstacles.push(obstacle);
+        });
+        self.selected = Some(self.arena().obstacles.len() - 1);
+    }
+
+    fn delete_obstacle(&mut self, index: usize) {
+        self.edit(|arena| {
+            arena.obstacles.remove(index);
+        });
+        self.selected = None;
+    }
+
+    // A still obstacle starts moving down and back, a moving one stops
+    fn toggle_movement(&mut self, index: usize) {
+        self.edit(|arena| {
+            let height = arena.height;
+            let obstacle = &mut arena.obstacles[index];
+            if obstacle.moves_to.is_some() {
+                obstacle.moves_to = None;
+                obstacle.speed = 0.;
+            } else {
+                let end_y = (obstacle.y + NEW_PATH_LENGTH).min(height);
+                obstacle.moves_to = Some([obstacle.x, end_y]);
+                obstacle.speed = NEW_PATH_SPEED;
+            }
+        });
+    }
+
+    fn change_speed(&mut self, index: usize, change: f32) {
+        self.edit(|arena| {
+            let obstacle = &mut arena.obstacles[index];
+            if obstacle.moves_to.is_some() {
+                obstacle.speed = (obstacle.speed + change).max(SPEED_STEP);
+            }
+        });
+    }
+
+    // Open the next arena from the list, replacing what's being edited
+    fn open_next(&mut self) {
+        if self.arenas.is_empty() {
+            return;
+        }
+        self.opened = (self.opened + 1) % self.arenas.len();
+        let arena = self.arenas[self.opened].clone();
+        self.edit(|current| *current = arena);
+        self.selected = None;
+        self.status = format!("Opened {}", self.arena().name);
+    }
+
+    fn save(&mut self, ctx: &mut Context, name: String) {
+        self.edit(|arena| arena.name = name);
+        self.status = match self.arena().save(ctx) {
+            Ok(path) => format!("Saved to {}", path),
+            Err(error) => format!("Could not save: {}", error),
+        };
+    }
+
+    fn test_play(&mut self, world: &mut World) {
+        world.arena = self.arena().clone();
+        world.set_mode(GameMode::VersusAi(world.settings.difficulty));
+        self.is_testing = true;
+    }
+
+    fn draw_grid(&self, ctx: &mut Context) {
+        let arena = self.arena();
+        let color = graphics::Color::from_rgba(40, 40, 40, 255);
+        let mut lines = Vec::new();
+        let mut x = GRID_SIZE;
+        while x < arena.width {
+            lines.push([na::Point2::new(x, 0.), na::Point2::new(x, arena.height)]);
+            x += GRID_SIZE;
+        }
+        let mut y = GRID_SIZE;
+        while y < arena.height {
+            lines.push([na::Point2::new(0., y), na::Point2::new(arena.width, y)]);
+            y += GRID_SIZE;
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+        let mut builder = graphics::MeshBuilder::new();
+        for line in lines.iter() {
+            builder.line(line, 1., color).unwrap();
+        }
+        let mesh = builder.build(ctx).unwrap();
+        graphics::draw(ctx, &mesh, DrawParam::default()).unwrap();
+    }
+
+    // Obstacle paths, the selection and the spawn and goal handles, all in one
+    // mesh
+    fn draw_handles(&self, ctx: &mut Context) {
+        let outline = graphics::DrawMode::stroke(2.);
+        let handle_color = graphics::Color::from((192, 128, 64, 255));
+        let mut builder = graphics::MeshBuilder::new();
+
+        // Paths of moving obstacles with a handle at the far end
+        for (index, obstacle) in self.arena().obstacles.iter().enumerate() {
+            if let Some([x, y]) = obstacle.moves_to {
+                let end = na::Point2::new(x, y);
+                let line = [na::Point2::new(obstacle.x, obstacle.y), end];
+                builder.line(&line, 2., graphics::WHITE).unwrap();
+                if self.selected == Some(index) {
+                    let rect = Self::handle_rect(end);
+                    builder.rectangle(graphics::DrawMode::fill(), rect, handle_color);
+                }
+            }
+        }
+
+        if let Some(index) = self.selected {
+            let obstacle = &self.arena().obstacles[index];
+            let rect = Self::obstacle_rect(obstacle);
+            let corner = Self::handle_rect(Self::resize_handle(obstacle));
+            builder.rectangle(outline, rect, handle_color);
+            builder.rectangle(graphics::DrawMode::fill(), corner, handle_color);
+        }
+
+        for side in 0..2 {
+            let spawn = self.spawn_rect(side);
+            builder.rectangle(outline, spawn, graphics::WHITE);
+            let goal = Self::handle_rect(self.goal_handle(side));
+            builder.rectangle(graphics::DrawMode::fill(), goal, handle_color);
+        }
+
+        let mesh = builder.build(ctx).unwrap();
+        graphics::draw(ctx, &mesh, DrawParam::default()).unwrap();
+    }
+
+    fn draw_text(&self, ctx: &mut Context, text: &str, position: na::Point2<f32>) {
+        let text = Text::new(text);
+        graphics::draw(ctx, &text, DrawParam::default().dest(position)).unwrap();
+    }
+}
+
+impl Scene<World> for EditorScene {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
+        if self.is_done {
+            Some(Box::new(ms::new(ctx, world)))
+        } else if self.is_testing {
+            world.reset(ctx);
+            let session =
+                std::mem::replace(&mut self.session, EditorSession::new(Arena::classic()));
+            Some(Box::new(gs::test_play(ctx, session)))
+        } else {
+            None
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _world: &mut World) {
+        if self.snap {
+            self.draw_grid(ctx);
+        }
+        self.arena().draw(ctx);
+
+        self.draw_handles(ctx);
+
+        let arena = self.arena();
+        let snap = if self.snap { "on" } else { "off" };
+        let header = format!(
+            "{} ({}x{})      Snap: {}",
+            arena.name, arena.width, arena.height, snap
+        );
+        self.draw_text(ctx, &header, na::Point2::new(16., 16.));
+        if let Some(index) = self.selected {
+            let obstacle = &arena.obstacles[index];
+            let details = match obstacle.moves_to {
+                Some(_) => format!(
+                    "{}x{}, moving at {}",
+                    obstacle.width, obstacle.height, obstacle.speed
+                ),
+                None => format!("{}x{}", obstacle.width, obstacle.height),
+            };
+            self.draw_text(ctx, &details, na::Point2::new(16., 36.));
+        }
+        self.draw_text(ctx, &self.status, na::Point2::new(16., 56.));
+
+        let help = [
+            "Click: place or select   Right click, Delete: remove   M: moving   +/-: speed",
+            "G: snap   Z/Y: undo/redo   Tab: open next   T: test-play   Return: save",
+        ];
+        for (index, line) in help.iter().enumerate() {
+            let y = arena.height - 48. + index as f32 * 20.;
+            self.draw_text(ctx, line, na::Point2::new(16., y));
+        }
+
+        if let Some(name_input) = &self.name_input {
+            let overlay = graphics::Rect::new(0., 0., arena.width, arena.height);
+            let color = graphics::Color::from_rgba(0, 0, 0, 192);
+            let mesh =
+                graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), overlay, color)
+                    .unwrap();
+            graphics::draw(ctx, &mesh, DrawParam::default()).unwrap();
+            let center = arena.center();
+            self.draw_text(
+                ctx,
+                "Save arena as",
+                na::Point2::new(center.x - 120., center.y - 40.),
+            );
+            name_input.draw(ctx, na::Point2::new(center.x - 120., center.y - 10.));
+        }
+    }
+
+    fn input(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        keycode: KeyCode,
+        _pressed: bool,
+        _repeat: bool,
+    ) {
+        if let Some(name_input) = &mut self.name_input {
+            match name_input.input(keycode) {
+                Some(TextInputEvent::Submitted(name)) => {
+                    self.name_input = None;
+                    self.save(ctx, name);
+                }
+                Some(TextInputEvent::Cancelled) => self.name_input = None,
+                None => {}
+            }
+            return;
+        }
+
+        match (keycode, self.selected) {
+            (KeyCode::Back, _) => self.is_done = true,
+            (KeyCode::G, _) => self.snap = !self.snap,
+            (KeyCode::Z, _) => self.undo(),
+            (KeyCode::Y, _) => self.redo(),
+            (KeyCode::Tab, _) => self.open_next(),
+            (KeyCode::T, _) => self.test_play(world),
+            // Not a letter key, its character would end up in the name
+            (KeyCode::Return, _) => {
+                let name_input = TextInput::new(ctx, MAX_NAME_LENGTH).with_text(&self.arena().name);
+                self.name_input = Some(name_input);
+            }
+            (KeyCode::Delete, Some(index)) => self.delete_obstacle(index),
+            (KeyCode::M, Some(index)) => self.toggle_movement(index),
+            (KeyCode::Equals, Some(index)) | (KeyCode::Add, Some(index)) => {
+                self.change_speed(index, SPEED_STEP)
+            }
+            (KeyCode::Minus, Some(index)) | (KeyCode::Subtract, Some(index)) => {
+                self.change_speed(index, -SPEED_STEP)
+            }
+            _ => {}
+        }
+    }
+
+    fn text_input(&mut self, _ctx: &mut Context, _world: &mut World, character: char) {
+        if let Some(name_input) = &mut self.name_input {
+            name_input.text_input(character);
+        }
+    }
+
+    fn mouse_motion(&mut self, _ctx: &mut Context, _world: &mut World, x: f32, y: f32) {
+        self.drag_motion(na::Point2::new(x, y));
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut World,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) {
+        if self.name_input.is_some() {
+            return;
+        }
+
+        let point = na::Point2::new(x, y);
+        match (button, self.handle_at(point)) {
+            (MouseButton::Left, Some((handle, center))) => {
+                match handle {
+                    Handle::Obstacle(index) => self.selected = Some(index),
+                    Handle::Goal(_) | Handle::Spawn(_) => self.selected = None,
+                    _ => {}
+                }
+                self.start_drag(handle, center - point);
+            }
+            (MouseButton::Left, None) => self.place_obstacle(point),
+            (MouseButton::Right, Some((Handle::Obstacle(index), _))) => self.delete_obstacle(index),
+            _ => {}
+        }
+    }
+
+    fn mouse_button_up(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut World,
+        button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) {
+        if button == MouseButton::Left {
+            self.end_drag();
+        }
+    }
+
+    fn arena_size(&self, _world: &World) -> (f32, f32) {
+        (self.arena().width, self.arena().height)
+    }
+
+    fn name(&self) -> &str {
+        "Editor Scene"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor() -> EditorScene {
+        EditorScene::with_arenas(EditorSession::new(Arena::classic()), vec![Arena::classic()])
+    }
+
+    fn obstacle_at(editor: &EditorScene, index: usize) -> (f32, f32) {
+        let obstacle = &editor.arena().obstacles[index];
+        (obstacle.x, obstacle.y)
+    }
+
+    #[test]
+    fn editing_during_a_drag_ends_it() {
+        let mut editor = editor();
+        editor.place_obstacle(na::Point2::new(400., 300.));
+        let index = editor.arena().obstacles.len() - 1;
+
+        editor.start_drag(Handle::Resize(index), na::Vector2::zeros());
+        editor.drag_motion(na::Point2::new(500., 400.));
+        editor.delete_obstacle(index);
+        assert!(editor.drag.is_none());
+        // Would index the deleted obstacle if the drag were still going
+        editor.drag_motion(na::Point2::new(520., 420.));
+
+        let edits: [fn(&mut EditorScene); 3] =
+            [EditorScene::undo, EditorScene::redo, EditorScene::open_next];
+        for edit in edits.iter() {
+            editor.place_obstacle(na::Point2::new(400., 300.));
+            let index = editor.arena().obstacles.len() - 1;
+            editor.start_drag(Handle::Obstacle(index), na::Vector2::zeros());
+            edit(&mut editor);
+            assert!(editor.drag.is_none());
+            editor.drag_motion(na::Point2::new(100., 100.));
+        }
+    }
+
+    #[test]
+    fn drag_interrupted_by_undo_is_undone_in_one_go() {
+        let mut editor = editor();
+        let obstacles = editor.arena().obstacles.len();
+        editor.place_obstacle(na::Point2::new(400., 300.));
+
+        editor.start_drag(Handle::Obstacle(obstacles), na::Vector2::zeros());
+        editor.drag_motion(na::Point2::new(200., 200.));
+        editor.drag_motion(na::Point2::new(100., 100.));
+        editor.undo();
+        assert_eq!(obstacle_at(&editor, obstacles), (400., 300.));
+
+        editor.undo();
+        assert_eq!(editor.arena().obstacles.len(), obstacles);
+        editor.redo();
+        editor.redo();
+        assert_eq!(obstacle_at(&editor, obstacles), (100., 100.));
+    }
+}
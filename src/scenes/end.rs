@@ -1,57 +1,264 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use crate::{
-    game::GameScene as gs, graphics, menu::MenuScene as ms, na, Context, KeyCode, Scene, World,
+    game::GameScene as gs,
+    graphics,
+    menu::MenuScene as ms,
+    mode::FREE_FOR_ALL_LIVES,
+    na,
+    ui::menu::{Menu, MenuEvent, MenuItem},
+    viewport::ARENA_WIDTH,
+    Context, DrawParam, KeyCode, MouseButton, Scene, Text, World,
 };
 
+const PANEL_TOP: f32 = 120.;
+const ROW_HEIGHT: f32 = 24.;
+const GRAPH_HEIGHT: f32 = 80.;
+const GRAPH_WIDTH: f32 = 400.;
+const MENU_TOP: f32 = 430.;
+// Room left for the export message between the graph and the menu
+const EXPORT_LINE_HEIGHT: f32 = 28.;
+
+#[derive(Clone, Copy, PartialEq)]
+enum EndAction {
+    Retry,
+    ExportStats,
+    MainMenu,
+}
+
 pub struct EndScene {
     title_text: graphics::Text,
 
     is_done: bool,
     is_retry: bool,
 
-    selected_item_index: i32,
-    menu_items: Vec<graphics::Text>,
+    stats_rows: Vec<(Text, Text)>,
+    // Only shown for two players, after wall practice there's no opponent to lead
+    momentum: Option<Vec<i32>>,
+    export_text: Option<Text>,
 
-    silver_color: graphics::Color,
+    menu: Menu<EndAction>,
 }
 
 impl EndScene {
-    pub fn new(ctx: &mut Context, winner: String) -> Self {
-        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
-
+    pub fn new(ctx: &mut Context, winner: String, world: &World) -> Self {
+        let stats = &world.stats;
+        let is_practice = world.get_mode().is_practice();
         let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
-        let winner_text: String = format!("{} is the winner!", winner);
+        let winner_text: String = if is_practice {
+            String::from("Game over")
+        } else {
+            format!("{} is the winner!", winner)
+        };
         let title_text_fragment = graphics::TextFragment::new(winner_text)
             .color(graphics::Color::from((192, 128, 64, 255)))
             .font(font)
             .scale(graphics::Scale::uniform(56.0));
 
-        let items: Vec<graphics::Text> = ["Retry", "Main Menu"]
-            .iter()
-            .map(|title| {
-                graphics::Text::new(
-                    graphics::TextFragment::new(*title)
-                        .color(silver)
-                        .font(font)
-                        .scale(graphics::Scale::uniform(36.0)),
-                )
-            })
-            .collect();
+        let items = vec![
+            MenuItem::action(EndAction::Retry, "Retry"),
+            MenuItem::action(EndAction::ExportStats, "Export stats"),
+            MenuItem::action(EndAction::MainMenu, "Main Menu"),
+        ];
 
         Self {
             title_text: graphics::Text::new(title_text_fragment),
             is_done: false,
             is_retry: false,
-            selected_item_index: 0,
-            menu_items: items,
-            silver_color: silver,
+            stats_rows: if is_practice {
+                Self::practice_rows(world)
+            } else {
+                Self::stats_rows(world)
+            },
+            momentum: if is_practice || stats.players.len() != 2 {
+                None
+            } else {
+                Some(stats.momentum())
+            },
+            export_text: None,
+            menu: Menu::new(ctx, items),
+        }
+    }
+
+    fn stats_rows(world: &World) -> Vec<(Text, Text)> {
+        let stats = &world.stats;
+        let mode = world.get_mode();
+        let per_player = |values: &[u32]| {
+            let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+            values.join("  -  ")
+        };
+        // Teammates' numbers add up, everyone else is a team of one
+        let per_team = |values: &[u32]| {
+            let mut totals = vec![0; mode.team_count()];
+            for (player, value) in world.players.iter().zip(values) {
+                totals[player.team] += value;
+            }
+            per_player(&totals)
+        };
+
+        let teams: Vec<String> = (0..mode.team_count())
+            .map(|team| world.team_name(team))
+            .collect();
+        let names = teams.join(" vs ");
+        let score = world.scoring.match_score();
+
+        // A free-for-all counts the goals let in, show the lives left instead
+        let mut rows = if mode.is_free_for_all() {
+            let lives: Vec<u32> = score
+                .iter()
+                .map(|points| (FREE_FOR_ALL_LIVES - points).max(0) as u32)
+                .collect();
+            vec![
+                (names, String::new()),
+                (String::from("Lives left"), per_player(&lives)),
+            ]
+        } else {
+            let score: Vec<u32> = score.iter().map(|points| *points as u32).collect();
+            vec![(names, per_player(&score))]
+        };
+        // Points of every set, e.g. "11-7, 5-11, 11-9"
+        if world.scoring.is_multi_set() {
+            let sets: Vec<String> = world
+                .scoring
+                .sets()
+                .iter()
+                .map(|set| format!("{}-{}", set[0], set[1]))
+                .collect();
+            rows.push((String::from("Sets"), sets.join(", ")));
+        }
+        if mode.has_bricks() {
+            rows.push((String::from("Bricks broken"), per_team(&stats.bricks)));
+            rows.push((String::from("Brick points"), per_team(&stats.brick_points)));
+        }
+
+        rows.extend(vec![
+            (String::from("Hits"), per_team(&stats.hits)),
+            (
+                String::from("Points won on serve"),
+                per_team(&stats.points_won_on_serve),
+            ),
+            (
+                String::from("Total rally hits"),
+                stats.total_rally_hits().to_string(),
+            ),
+            (
+                String::from("Longest rally"),
+                stats.longest_rally().to_string(),
+            ),
+            (
+                String::from("Ball speed avg / max"),
+                format!("{:.0} / {:.0}", stats.average_speed(), stats.max_speed),
+            ),
+        ]);
+
+        rows.into_iter()
+            .map(|(label, value)| (Text::new(label), Text::new(value)))
+            .collect()
+    }
+
+    // Wall practice is about the returns and how fast the ball got
+    fn practice_rows(world: &World) -> Vec<(Text, Text)> {
+        let stats = &world.stats;
+        let best = world
+            .high_scores
+            .entries(world.get_mode())
+            .first()
+            .map(|entry| format!("{} ({})", entry.score, entry.name))
+            .unwrap_or_else(|| String::from("-"));
+
+        vec![
+            (String::from("Returns"), stats.hits[0].to_string()),
+            (
+                String::from("Longest rally"),
+                stats.longest_rally().to_string(),
+            ),
+            (
+                String::from("Ball speed avg / max"),
+                format!("{:.0} / {:.0}", stats.average_speed(), stats.max_speed),
+            ),
+            (String::from("Best"), best),
+        ]
+        .into_iter()
+        .map(|(label, value)| (Text::new(label), Text::new(value)))
+        .collect()
+    }
+
+    fn export_stats(&mut self, ctx: &mut Context, world: &World) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        let path = format!("/match-stats-{}.json", timestamp);
+
+        let message = match world.stats.export(ctx, &path) {
+            Ok(_) => format!("Saved {}", path),
+            Err(error) => format!("Could not export stats: {}", error),
+        };
+        self.export_text = Some(Text::new(message));
+    }
+
+    // Running lead after every point, above the line means player 1 was ahead
+    fn draw_momentum(&self, ctx: &mut Context, momentum: &[i32], position: na::Point2<f32>) {
+        let baseline = graphics::Mesh::new_line(
+            ctx,
+            &[na::Point2::new(0., 0.), na::Point2::new(GRAPH_WIDTH, 0.)],
+            1.,
+            graphics::Color::new(0.4, 0.4, 0.4, 1.),
+        )
+        .unwrap();
+        graphics::draw(ctx, &baseline, DrawParam::default().dest(position)).unwrap();
+
+        if momentum.is_empty() {
+            return;
+        }
+
+        let max_lead = momentum
+            .iter()
+            .map(|lead| lead.abs())
+            .max()
+            .unwrap_or(1)
+            .max(1);
+        let step_x = GRAPH_WIDTH / momentum.len() as f32;
+        let step_y = (GRAPH_HEIGHT * 0.5) / max_lead as f32;
+
+        let mut points = vec![na::Point2::new(0., 0.)];
+        points.extend(momentum.iter().enumerate().map(|(index, lead)| {
+            na::Point2::new((index + 1) as f32 * step_x, -(*lead as f32) * step_y)
+        }));
+
+        let graph =
+            graphics::Mesh::new_line(ctx, &points, 2., graphics::Color::from((192, 128, 64, 255)))
+                .unwrap();
+        graphics::draw(ctx, &graph, DrawParam::default().dest(position)).unwrap();
+    }
+
+    fn handle_event(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        event: Option<MenuEvent<EndAction>>,
+    ) {
+        let feedback_sound = event.as_ref().map(|event| event.feedback_sound());
+
+        match event {
+            Some(MenuEvent::Activated(EndAction::Retry)) => self.is_retry = true,
+            Some(MenuEvent::Activated(EndAction::ExportStats)) => self.export_stats(ctx, world),
+            Some(MenuEvent::Activated(EndAction::MainMenu)) | Some(MenuEvent::Back) => {
+                self.is_done = true
+            }
+            _ => {}
+        };
+
+        if let Some(sound) = feedback_sound {
+            world.play_sound(ctx, sound);
         }
     }
 }
 
 impl Scene<World> for EndScene {
-    fn update(&mut self, ctx: &mut Context, _world: &mut World) -> Option<Box<dyn Scene<World>>> {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
         if self.is_done {
-            Some(Box::new(ms::new(ctx)))
+            Some(Box::new(ms::new(ctx, world)))
         } else if self.is_retry {
             Some(Box::new(gs::new(ctx)))
         } else {
@@ -60,34 +267,10 @@ impl Scene<World> for EndScene {
     }
 
     fn draw(&mut self, ctx: &mut Context, _world: &mut World) {
-        let (scr_width, scr_height) = graphics::drawable_size(ctx);
+        let scr_width = ARENA_WIDTH;
 
         let title_text_width = self.title_text.width(ctx) as f32;
-        let title_text_height = self.title_text.height(ctx) as f32;
-
-        let title_text_dest = na::Point2::new(
-            (scr_width as f32 / 2.0) - (title_text_width / 2.0),
-            (scr_height as f32 / 2.0) - (title_text_height + 20.0),
-        );
-
-        for (index, item) in self.menu_items.iter_mut().enumerate() {
-            let height = item.height(ctx) as f32;
-
-            let dest = na::Point2::new(
-                (scr_width as f32 / 2.0) - (title_text_width as f32 / 2.0),
-                (scr_height as f32 / 2.0)
-                    + ((title_text_height + 20.0) - (height + 20.0))
-                    + (index as f32 * 50.),
-            );
-
-            if index as i32 == self.selected_item_index {
-                item.fragments_mut()[0].color = Some(graphics::WHITE);
-            } else {
-                item.fragments_mut()[0].color = Some(self.silver_color);
-            }
-
-            graphics::draw(ctx, item, graphics::DrawParam::new().dest(dest)).unwrap();
-        }
+        let title_text_dest = na::Point2::new((scr_width / 2.0) - (title_text_width / 2.0), 30.);
 
         graphics::draw(
             ctx,
@@ -95,38 +278,93 @@ impl Scene<World> for EndScene {
             graphics::DrawParam::new().dest(title_text_dest),
         )
         .unwrap();
+
+        // Stats panel, labels to the left and values right aligned. Rows squeeze
+        // together when there are too many to fit above the menu.
+        let graph_height = if self.momentum.is_some() {
+            GRAPH_HEIGHT
+        } else {
+            0.
+        };
+        let rows = self.stats_rows.len().max(1) as f32;
+        let room = MENU_TOP - PANEL_TOP - 20. - graph_height - EXPORT_LINE_HEIGHT;
+        let row_height = ROW_HEIGHT.min(room / rows);
+        let (panel_left, panel_right) = (scr_width * 0.2, scr_width * 0.8);
+        for (index, (label, value)) in self.stats_rows.iter().enumerate() {
+            let y = PANEL_TOP + index as f32 * row_height;
+            let value_x = panel_right - value.width(ctx) as f32;
+            graphics::draw(
+                ctx,
+                label,
+                DrawParam::default().dest(na::Point2::new(panel_left, y)),
+            )
+            .unwrap();
+            graphics::draw(
+                ctx,
+                value,
+                DrawParam::default().dest(na::Point2::new(value_x, y)),
+            )
+            .unwrap();
+        }
+
+        let graph_top = PANEL_TOP + self.stats_rows.len() as f32 * row_height + 20.;
+        if let Some(momentum) = &self.momentum {
+            self.draw_momentum(
+                ctx,
+                momentum,
+                na::Point2::new(
+                    (scr_width - GRAPH_WIDTH) * 0.5,
+                    graph_top + GRAPH_HEIGHT * 0.5,
+                ),
+            );
+        }
+
+        if let Some(export_text) = &self.export_text {
+            let x = (scr_width - export_text.width(ctx) as f32) * 0.5;
+            let y = graph_top + graph_height + 8.;
+            graphics::draw(
+                ctx,
+                export_text,
+                DrawParam::default().dest(na::Point2::new(x, y)),
+            )
+            .unwrap();
+        }
+
+        self.menu
+            .draw(ctx, na::Point2::new(scr_width * 0.5 - 120., MENU_TOP));
     }
 
     fn input(
         &mut self,
-        _ctx: &mut Context,
-        _world: &mut World,
+        ctx: &mut Context,
+        world: &mut World,
         keycode: KeyCode,
         _pressed: bool,
         _repeat: bool,
     ) {
-        match keycode {
-            KeyCode::Space | KeyCode::Return | KeyCode::D | KeyCode::Right => {
-                if self.selected_item_index == 0 {
-                    self.is_retry = true;
-                } else {
-                    self.is_done = true;
-                }
-            }
-            KeyCode::Up | KeyCode::W => {
-                let item_count = self.menu_items.len() as i32;
-                self.selected_item_index = if self.selected_item_index == 0 {
-                    item_count - 1
-                } else {
-                    (self.selected_item_index - 1) % item_count
-                };
-            }
-            KeyCode::Down | KeyCode::S => {
-                let item_count = self.menu_items.len() as i32;
-                self.selected_item_index = (self.selected_item_index + 1) % item_count;
-            }
-            _ => {}
+        let event = self.menu.input(keycode);
+        self.handle_event(ctx, world, event);
+    }
+
+    fn mouse_motion(&mut self, ctx: &mut Context, world: &mut World, x: f32, y: f32) {
+        let event = self.menu.mouse_motion(x, y);
+        self.handle_event(ctx, world, event);
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) {
+        let event = match button {
+            MouseButton::Left => self.menu.mouse_click(x, y),
+            MouseButton::Right => self.menu.input(KeyCode::Back),
+            _ => None,
         };
+        self.handle_event(ctx, world, event);
     }
 
     fn name(&self) -> &str {
@@ -1,69 +1,398 @@
 use crate::{
-    collidable::Collidable, end::EndScene as es, graphics, na, Context, DrawParam, KeyCode, Scene,
-    Text, World,
+    bracket::Tournament,
+    editor::{EditorScene as eds, EditorSession},
+    end::EndScene as es,
+    events::GameEvent,
+    graphics,
+    mode::GameMode,
+    mode::FREE_FOR_ALL_LIVES,
+    na,
+    powerups::PowerUpKind,
+    scoreboard::ScoreboardScene as sbs,
+    side::Side,
+    sound::Sound,
+    tournament::TournamentScene as ts,
+    world::{SHIELD_THICKNESS, WALL_THICKNESS},
+    Context, DrawParam, KeyCode, Scene, Text, World,
 };
 
-pub struct GameScene {}
+pub struct GameScene {
+    has_served: bool,
+    // The simulation, clock and effects are frozen while paused
+    is_paused: bool,
+    pause_text: Text,
+    // Set when test-playing an arena from the editor, which the match returns to
+    editor: Option<EditorSession>,
+    is_stopped: bool,
+}
 
 impl GameScene {
-    pub fn new(_ctx: &mut Context) -> Self {
-        Self {}
+    pub fn new(ctx: &mut Context) -> Self {
+        let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
+        let pause_text_fragment = graphics::TextFragment::new("Paused")
+            .color(graphics::Color::from((192, 128, 64, 255)))
+            .font(font)
+            .scale(graphics::Scale::uniform(56.0));
+
+        Self {
+            has_served: false,
+            is_paused: false,
+            pause_text: Text::new(pause_text_fragment),
+            editor: None,
+            is_stopped: false,
+        }
+    }
+
+    pub fn test_play(ctx: &mut Context, session: EditorSession) -> Self {
+        Self {
+            editor: Some(session),
+            ..Self::new(ctx)
+        }
+    }
+
+    // Dim the arena and show how to carry on
+    fn draw_pause_overlay(&self, ctx: &mut Context, world: &World) {
+        let (scr_width, scr_height) = (world.arena.width, world.arena.height);
+
+        let rect = graphics::Rect::new(0., 0., scr_width, scr_height);
+        let overlay = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            rect,
+            graphics::Color::from_rgba(0, 0, 0, 160),
+        )
+        .unwrap();
+        graphics::draw(ctx, &overlay, DrawParam::default()).unwrap();
+
+        let title_position = na::Point2::new(
+            (scr_width - self.pause_text.width(ctx) as f32) * 0.5,
+            scr_height * 0.5 - self.pause_text.height(ctx) as f32,
+        );
+        graphics::draw(
+            ctx,
+            &self.pause_text,
+            DrawParam::default().dest(title_position),
+        )
+        .unwrap();
+
+        let hint_text = Text::new("Press P to resume");
+        let hint_position = na::Point2::new(
+            (scr_width - hint_text.width(ctx) as f32) * 0.5,
+            scr_height * 0.5 + 20.,
+        );
+        graphics::draw(ctx, &hint_text, DrawParam::default().dest(hint_position)).unwrap();
+    }
+
+    // A wall along the inside of an edge, e.g. the wall in wall practice
+    fn draw_wall(
+        &self,
+        ctx: &mut Context,
+        world: &World,
+        side: Side,
+        thickness: f32,
+        color: graphics::Color,
+    ) {
+        let (width, height) = (world.arena.width, world.arena.height);
+        let rect = match side {
+            Side::Left => graphics::Rect::new(0., 0., thickness, height),
+            Side::Right => graphics::Rect::new(width - thickness, 0., thickness, height),
+            Side::Top => graphics::Rect::new(0., 0., width, thickness),
+            Side::Bottom => graphics::Rect::new(0., height - thickness, width, thickness),
+        };
+        let wall =
+            graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, color).unwrap();
+        graphics::draw(ctx, &wall, DrawParam::default()).unwrap();
+    }
+
+    // Horizontal centre of each team's column in the HUD
+    fn column_centers(world: &World) -> Vec<f32> {
+        let scr_width = world.arena.width;
+        let teams = world.get_mode().team_count();
+        if teams == 2 {
+            vec![scr_width * 0.25, scr_width * 0.75]
+        } else {
+            (0..teams)
+                .map(|team| scr_width * (0.2 + 0.2 * team as f32))
+                .collect()
+        }
+    }
+
+    // Active power-ups under each team's name with the seconds left
+    fn draw_effects(&self, ctx: &mut Context, world: &World) {
+        let centers = Self::column_centers(world);
+        // Effects on both players of a team are listed once
+        let mut shown: Vec<(usize, PowerUpKind)> = Vec::new();
+        for (player, team) in world.players.iter().map(|player| player.team).enumerate() {
+            let center_x = centers[team];
+            for effect in world.power_ups.effects(player) {
+                if shown.contains(&(team, effect.kind)) {
+                    continue;
+                }
+                let index = shown.iter().filter(|(other, _)| *other == team).count();
+                shown.push((team, effect.kind));
+                let effect_text = Text::new(
+                    graphics::TextFragment::new(format!(
+                        "{} {:.0}",
+                        effect.kind.name(),
+                        effect.remaining.ceil()
+                    ))
+                    .color(effect.kind.color()),
+                );
+                let effect_position = na::Point2::new(
+                    center_x - effect_text.width(ctx) as f32 * 0.5,
+                    64. + index as f32 * 20.,
+                );
+                graphics::draw(
+                    ctx,
+                    &effect_text,
+                    DrawParam::default().dest(effect_position),
+                )
+                .unwrap();
+            }
+        }
     }
 }
 
 impl Scene<World> for GameScene {
     fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
-        let dt = world.get_dt();
-        world.player_1.update(ctx, dt);
-        world.player_2.update(ctx, dt);
+        if !self.has_served {
+            world.particles.clear();
+            world.juice.reset();
+            world.begin_match();
+            world.serve(ctx);
+            self.has_served = true;
+        }
 
-        world.ball.update(ctx, dt);
+        if self.is_stopped {
+            if let Some(session) = self.editor.take() {
+                return Some(Box::new(eds::resume(ctx, session)));
+            }
+        }
 
-        // Collision check
-        if world.player_1.paddle.check_collision(&world.ball) {
-            world.ball.velocity.x = world.ball.velocity.x.abs();
+        if self.is_paused {
+            return None;
         }
 
-        if world.player_2.paddle.check_collision(&world.ball) {
-            world.ball.velocity.x = -world.ball.velocity.x.abs();
+        let dt = world.get_dt();
+        if !world.juice.is_hit_stopped() {
+            world.step(ctx);
         }
+        let ball_positions = world.balls.iter().map(|ball| ball.position).collect();
+        world.juice.update(&world.settings, dt, ball_positions);
+        world.particles.update(dt);
+
+        for event in world.dispatch_events(ctx) {
+            if let GameEvent::MatchWon { winner } = event {
+                if let Some(session) = self.editor.take() {
+                    return Some(Box::new(eds::resume(ctx, session)));
+                }
+
+                let winner_name = world.team_name(winner);
+
+                // Back to the bracket between tournament matches
+                if let Some(tournament) = &mut world.tournament {
+                    let score = world.scoring.match_score();
+                    tournament.record(winner == 0, [score[0] as usize, score[1] as usize]);
+                    // A finished tournament has nothing left to resume
+                    if tournament.champion().is_some() {
+                        Tournament::remove(ctx);
+                    } else {
+                        tournament.save(ctx);
+                    }
+                    return Some(Box::new(ts::new(ctx, world)));
+                }
+
+                // Solo modes ask for a name first when the score makes the table
+                let mode = world.get_mode();
+                if let Some(score) = mode.solo_score(&world.stats) {
+                    if world.high_scores.qualifies(mode, score) {
+                        let scene = sbs::new_record(ctx, world, score, winner_name);
+                        return Some(Box::new(scene));
+                    }
+                }
 
-        if let Some(winner) = world.check_score(ctx) {
-            return Some(Box::new(es::new(ctx, String::from(winner))));
+                return Some(Box::new(es::new(ctx, winner_name, world)));
+            }
         }
 
         None
     }
 
     fn draw(&mut self, ctx: &mut Context, world: &mut World) {
-        world.player_1.draw(ctx);
-        world.player_2.draw(ctx);
-        world.ball.draw(ctx);
-
-        // Draw score UI
-        let score_text = Text::new(format!(
-            "{}      {}",
-            world.player_1.get_score(),
-            world.player_2.get_score()
-        ));
-
-        let scr_width = graphics::drawable_size(ctx).0;
+        // Everything in the arena moves with the screen shake, the score UI stays put
+        let shake = world.juice.shake_offset();
+        graphics::push_transform(
+            ctx,
+            Some(
+                DrawParam::new()
+                    .dest(na::Point2::new(shake.x, shake.y))
+                    .to_matrix(),
+            ),
+        );
+        graphics::apply_transformations(ctx).unwrap();
+
+        let scr_width = world.arena.width;
+        world.arena.draw(ctx);
+        world.bricks.draw(ctx);
+
+        // The wall in wall practice and the edges of players out of the match
+        let wall_color = graphics::Color::from_rgba(192, 192, 192, 255);
+        for side in world.closed_edges() {
+            self.draw_wall(ctx, world, side, WALL_THICKNESS, wall_color);
+        }
+
+        let shield_color = PowerUpKind::Shield.color();
+        for (index, player) in world.players.iter().enumerate() {
+            if !world.is_in_play(index) {
+                continue;
+            }
+            player.draw(ctx);
+
+            // Shield walls behind the paddles
+            if world.power_ups.is_active(PowerUpKind::Shield, index) {
+                self.draw_wall(ctx, world, player.side, SHIELD_THICKNESS, shield_color);
+            }
+        }
+        world.power_ups.draw(ctx);
+        if let Some(ball) = world.balls.first() {
+            world.juice.draw_trail(ctx, ball.get_mesh());
+        }
+        for ball in &world.balls {
+            ball.draw(ctx, world.juice.ball_scale());
+        }
+        world.particles.draw(ctx);
+
+        graphics::pop_transform(ctx);
+        graphics::apply_transformations(ctx).unwrap();
+
+        // Draw score UI, survival and wall practice show the lives left instead of the
+        // opponent's score, wall practice counts returns rather than points
+        let scoring = &world.scoring;
+        let mode = world.get_mode();
+        let score_text = match (mode, mode.lives()) {
+            (GameMode::Practice(_), Some(lives)) => Text::new(format!(
+                "Returns: {}      Lives: {}",
+                world.stats.hits[0],
+                lives - scoring.points(1)
+            )),
+            (_, Some(lives)) => Text::new(format!(
+                "{}      Lives: {}",
+                scoring.points(0),
+                lives - scoring.points(1)
+            )),
+            _ => Text::new(format!("{}      {}", scoring.points(0), scoring.points(1))),
+        };
+
         let scr_width_half = scr_width * 0.5;
         let score_position = na::Point2::new(scr_width_half, 40.);
 
-        let mut draw_param = DrawParam::default();
-        draw_param.dest = score_position.into();
-        graphics::draw(ctx, &score_text, draw_param).unwrap();
+        // Every player shows their own lives in a free-for-all
+        if !mode.is_free_for_all() {
+            let draw_param = DrawParam::default().dest(score_position);
+            graphics::draw(ctx, &score_text, draw_param).unwrap();
+        }
+
+        // Set score and deuce status below the points
+        let mut status_lines = Vec::new();
+        if scoring.is_multi_set() {
+            status_lines.push(format!(
+                "Sets  {} - {}",
+                scoring.sets_won(0),
+                scoring.sets_won(1)
+            ));
+        }
+        if let Some(status) = scoring.status() {
+            status_lines.push(status);
+        }
+        if mode.has_bricks() {
+            status_lines.push(format!(
+                "Bricks  {} - {}",
+                world.brick_points(0),
+                world.brick_points(1)
+            ));
+        }
+        for (index, line) in status_lines.iter().enumerate() {
+            let status_text = Text::new(line.as_str());
+            let status_position = na::Point2::new(
+                scr_width_half - status_text.width(ctx) as f32 * 0.5 + 20.,
+                64. + index as f32 * 20.,
+            );
+            graphics::draw(
+                ctx,
+                &status_text,
+                DrawParam::default().dest(status_position),
+            )
+            .unwrap();
+        }
+
+        // Countdown of a timed match above the score
+        if let Some(clock) = &world.clock {
+            let clock_text = Text::new(clock.describe());
+            let clock_position = na::Point2::new(
+                scr_width_half - clock_text.width(ctx) as f32 * 0.5 + 20.,
+                16.,
+            );
+            graphics::draw(ctx, &clock_text, DrawParam::default().dest(clock_position)).unwrap();
+        }
+
+        // Draw team names above each half, wall practice shows the ball speed on the right
+        // and a free-for-all shows the lives of every player in their colour
+        let labels: Vec<Text> = if mode.is_free_for_all() {
+            world
+                .players
+                .iter()
+                .enumerate()
+                .map(|(index, player)| {
+                    let lives = FREE_FOR_ALL_LIVES - scoring.points(index);
+                    let label = if world.is_eliminated(index) {
+                        format!("{}: out", player.get_name())
+                    } else {
+                        format!("{}: {}", player.get_name(), lives)
+                    };
+                    Text::new(graphics::TextFragment::new(label).color(player.get_color()))
+                })
+                .collect()
+        } else {
+            let right_label = if mode.is_practice() {
+                let speed = world.balls.first().map_or(0., |ball| ball.velocity.norm());
+                format!("Speed: {:.0}", speed)
+            } else {
+                world.team_name(1)
+            };
+            vec![Text::new(world.team_name(0)), Text::new(right_label)]
+        };
+        for (name_text, center_x) in labels.iter().zip(Self::column_centers(world)) {
+            let name_position = na::Point2::new(center_x - name_text.width(ctx) as f32 * 0.5, 40.);
+            graphics::draw(ctx, name_text, DrawParam::default().dest(name_position)).unwrap();
+        }
+
+        self.draw_effects(ctx, world);
+
+        if self.is_paused {
+            self.draw_pause_overlay(ctx, world);
+        }
     }
 
     fn input(
         &mut self,
-        _ctx: &mut Context,
-        _world: &mut World,
-        _keycode: KeyCode,
+        ctx: &mut Context,
+        world: &mut World,
+        keycode: KeyCode,
         _pressed: bool,
-        _repeat: bool,
+        repeat: bool,
     ) {
+        if keycode == KeyCode::P && !repeat {
+            self.is_paused = !self.is_paused;
+            world.play_sound(ctx, Sound::MenuConfirm);
+        }
+        // Backspace goes back to the editor when test-playing
+        if keycode == KeyCode::Back && self.editor.is_some() {
+            self.is_stopped = true;
+        }
+    }
+
+    fn arena_size(&self, world: &World) -> (f32, f32) {
+        (world.arena.width, world.arena.height)
     }
 
     fn name(&self) -> &str {
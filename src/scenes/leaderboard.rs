@@ -0,0 +1,222 @@
+use crate::{
+    graphics,
+    ladder::HeadToHead,
+    menu::MenuScene as ms,
+    na,
+    viewport::{ARENA_HEIGHT, ARENA_WIDTH},
+    Context, DrawParam, KeyCode, MouseButton, Scene, World,
+};
+
+const TABLE_TOP: f32 = 110.;
+const ROW_HEIGHT: f32 = 26.;
+const VISIBLE_ROWS: usize = 16;
+
+struct Row {
+    name: String,
+    rating: f32,
+    wins: u32,
+    losses: u32,
+    head_to_head: Vec<HeadToHead>,
+}
+
+pub struct LeaderboardScene {
+    title_text: graphics::Text,
+    font: graphics::Font,
+
+    rows: Vec<Row>,
+    selected: usize,
+    // First row shown when there are more rows than fit on screen
+    scroll: usize,
+
+    is_done: bool,
+}
+
+impl LeaderboardScene {
+    pub fn new(ctx: &mut Context, world: &World) -> Self {
+        let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
+        let title_text_fragment = graphics::TextFragment::new("Leaderboard")
+            .color(graphics::Color::from((192, 128, 64, 255)))
+            .font(font)
+            .scale(graphics::Scale::uniform(48.0));
+
+        let rows = world
+            .ladder
+            .standings()
+            .into_iter()
+            .map(|rating| Row {
+                name: rating.name.clone(),
+                rating: rating.rating,
+                wins: rating.wins,
+                losses: rating.losses,
+                head_to_head: world.ladder.head_to_head(&rating.name),
+            })
+            .collect();
+
+        Self {
+            title_text: graphics::Text::new(title_text_fragment),
+            font,
+            rows,
+            selected: 0,
+            scroll: 0,
+            is_done: false,
+        }
+    }
+
+    fn text(&self, text: String, color: graphics::Color) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(text)
+                .color(color)
+                .font(self.font)
+                .scale(graphics::Scale::uniform(22.0)),
+        )
+    }
+
+    fn select(&mut self, index: usize) {
+        if self.rows.is_empty() {
+            return;
+        }
+
+        self.selected = index.min(self.rows.len() - 1);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + VISIBLE_ROWS {
+            self.scroll = self.selected + 1 - VISIBLE_ROWS;
+        }
+    }
+}
+
+impl Scene<World> for LeaderboardScene {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
+        if self.is_done {
+            Some(Box::new(ms::new(ctx, world)))
+        } else {
+            None
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _world: &mut World) {
+        let (scr_width, scr_height) = (ARENA_WIDTH, ARENA_HEIGHT);
+        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
+        let highlight = graphics::Color::from((192, 128, 64, 255));
+
+        let title_text_width = self.title_text.width(ctx) as f32;
+        let title_dest = na::Point2::new((scr_width / 2.0) - (title_text_width / 2.0), 30.);
+        graphics::draw(ctx, &self.title_text, DrawParam::new().dest(title_dest)).unwrap();
+
+        if self.rows.is_empty() {
+            let empty_text = self.text(String::from("No matches played yet"), silver);
+            let dest = na::Point2::new(
+                (scr_width - empty_text.width(ctx) as f32) * 0.5,
+                scr_height * 0.5,
+            );
+            graphics::draw(ctx, &empty_text, DrawParam::new().dest(dest)).unwrap();
+            return;
+        }
+
+        // Standings on the left half
+        let columns = [40., 90., 290., 370.];
+        let headers = ["#", "Name", "Rating", "W - L"];
+        for (x, header) in columns.iter().zip(headers.iter()) {
+            let header_text = self.text(String::from(*header), highlight);
+            let dest = na::Point2::new(*x, TABLE_TOP - ROW_HEIGHT);
+            graphics::draw(ctx, &header_text, DrawParam::new().dest(dest)).unwrap();
+        }
+
+        for (index, row) in self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(VISIBLE_ROWS)
+        {
+            let color = if index == self.selected {
+                highlight
+            } else {
+                silver
+            };
+            let y = TABLE_TOP + (index - self.scroll) as f32 * ROW_HEIGHT;
+            let cells = [
+                format!("{}", index + 1),
+                row.name.clone(),
+                format!("{:.0}", row.rating),
+                format!("{} - {}", row.wins, row.losses),
+            ];
+            for (x, cell) in columns.iter().zip(cells.iter()) {
+                let cell_text = self.text(cell.clone(), color);
+                graphics::draw(
+                    ctx,
+                    &cell_text,
+                    DrawParam::new().dest(na::Point2::new(*x, y)),
+                )
+                .unwrap();
+            }
+        }
+
+        // Head-to-head records of the selected profile on the right half
+        let selected = &self.rows[self.selected];
+        let left = scr_width * 0.6;
+        let heading = self.text(format!("{} vs", selected.name), highlight);
+        graphics::draw(
+            ctx,
+            &heading,
+            DrawParam::new().dest(na::Point2::new(left, TABLE_TOP - ROW_HEIGHT)),
+        )
+        .unwrap();
+
+        for (index, record) in selected.head_to_head.iter().take(VISIBLE_ROWS).enumerate() {
+            let y = TABLE_TOP + index as f32 * ROW_HEIGHT;
+            let record_text = self.text(
+                format!("{}: {} - {}", record.opponent, record.wins, record.losses),
+                silver,
+            );
+            graphics::draw(
+                ctx,
+                &record_text,
+                DrawParam::new().dest(na::Point2::new(left, y)),
+            )
+            .unwrap();
+        }
+    }
+
+    fn input(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut World,
+        keycode: KeyCode,
+        _pressed: bool,
+        _repeat: bool,
+    ) {
+        match keycode {
+            KeyCode::Back | KeyCode::Return | KeyCode::Space => self.is_done = true,
+            KeyCode::Up | KeyCode::W => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::S => self.select(self.selected + 1),
+            _ => {}
+        };
+    }
+
+    fn mouse_motion(&mut self, _ctx: &mut Context, _world: &mut World, x: f32, y: f32) {
+        if x < ARENA_WIDTH * 0.6 && y >= TABLE_TOP {
+            let row = ((y - TABLE_TOP) / ROW_HEIGHT) as usize;
+            if row < VISIBLE_ROWS && self.scroll + row < self.rows.len() {
+                self.select(self.scroll + row);
+            }
+        }
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut World,
+        button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) {
+        if button == MouseButton::Right {
+            self.is_done = true;
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Leaderboard Scene"
+    }
+}
@@ -1,89 +1,551 @@
-use crate::{event, game::GameScene as gs, graphics, na, Context, KeyCode, Paddle, Scene, World};
+use crate::{
+    about::AboutScene as abs,
+    editor::EditorScene as eds,
+    event, graphics,
+    leaderboard::LeaderboardScene as lbs,
+    mode::{BallLaunch, Difficulty, GameMode},
+    na,
+    particles::{ParticleColor, LIFETIMES},
+    powerups::PowerUpKind,
+    scoreboard::ScoreboardScene as sbs,
+    scoring::ScoringKind,
+    settings::{MAX_PARTICLE_COUNT, MAX_POWER_UP_WEIGHT, MAX_VOLUME},
+    setup::SetupScene as ss,
+    tournament::TournamentScene as ts,
+    ui::menu::{Menu, MenuEvent, MenuItem},
+    viewport::{ARENA_HEIGHT, ARENA_WIDTH},
+    Context, KeyCode, MouseButton, Scene, World,
+};
+
+const BEST_OF: [(&str, i32); 3] = [("Single game", 1), ("Best of 3", 3), ("Best of 5", 5)];
+const TIME_LIMITS: [(&str, i32); 4] = [
+    ("1 minute", 1),
+    ("2 minutes", 2),
+    ("3 minutes", 3),
+    ("5 minutes", 5),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum MenuAction {
+    PlayVersus,
+    PlayVersusAi,
+    PlaySurvival,
+    PlayTimed,
+    PlayMultiBall,
+    PlayFreeForAll,
+    PlayDoubles,
+    PlayAirHockey,
+    PlayBreakout,
+    PlayPractice,
+    Tournament,
+    Editor,
+    Difficulty,
+    MaxScore,
+    Scoring,
+    BestOf,
+    TimeLimit,
+    SuddenDeathSpeedup,
+    BallLaunch,
+    PowerUps,
+    PowerUpWeight(PowerUpKind),
+    Fullscreen,
+    Volume,
+    Mute,
+    ParticleCount,
+    ParticleColor,
+    ParticleLifetime,
+    ReducedEffects,
+    ScreenShake,
+    HitStop,
+    BallTrail,
+    SquashStretch,
+    ClassicPreset,
+    JuicyPreset,
+    Leaderboard,
+    HighScores,
+    About,
+    Exit,
+}
 
 pub struct MenuScene {
     title_text: graphics::Text,
 
     is_done: bool,
+    is_about: bool,
+    is_leaderboard: bool,
+    is_high_scores: bool,
+    is_tournament: bool,
+    is_editor: bool,
 
-    selected_item_index: i32,
-    menu_items: Vec<graphics::Text>,
-
-    silver_color: graphics::Color,
-
-    left_indicator_paddle: Paddle,
-    right_indicator_paddle: Paddle,
+    menu: Menu<MenuAction>,
 }
 
 impl MenuScene {
-    pub fn new(ctx: &mut Context) -> Self {
-        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
-
+    pub fn new(ctx: &mut Context, world: &World) -> Self {
         let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
         let title_text_fragment = graphics::TextFragment::new("Pong-A-Long")
             .color(graphics::Color::from((192, 128, 64, 255)))
             .font(font)
             .scale(graphics::Scale::uniform(56.0));
 
-        // TODO: - add "Options", "About"
-        let items: Vec<graphics::Text> = ["Play", "Exit"]
+        let items = vec![
+            MenuItem::submenu("Play", Self::play_items(world)),
+            MenuItem::submenu("Options", Self::options_items(world)),
+            MenuItem::action(MenuAction::Editor, "Arena editor"),
+            MenuItem::action(MenuAction::Leaderboard, "Leaderboard"),
+            MenuItem::action(MenuAction::HighScores, "High scores"),
+            MenuItem::action(MenuAction::About, "About"),
+            MenuItem::action(MenuAction::Exit, "Exit"),
+        ];
+
+        Self {
+            title_text: graphics::Text::new(title_text_fragment),
+            is_done: false,
+            is_about: false,
+            is_leaderboard: false,
+            is_high_scores: false,
+            is_tournament: false,
+            is_editor: false,
+            menu: Menu::new(ctx, items),
+        }
+    }
+
+    fn play_items(world: &World) -> Vec<MenuItem<MenuAction>> {
+        let difficulty_names: Vec<&str> = Difficulty::ALL.iter().map(|d| d.name()).collect();
+        let difficulty_index = Difficulty::ALL
+            .iter()
+            .position(|d| *d == world.settings.difficulty)
+            .unwrap_or(1);
+
+        vec![
+            MenuItem::action(MenuAction::PlayVersus, "Versus"),
+            MenuItem::action(MenuAction::PlayVersusAi, "Versus computer"),
+            MenuItem::action(MenuAction::PlaySurvival, "Survival"),
+            MenuItem::action(MenuAction::PlayPractice, "Wall practice"),
+            // Local multiplayer matches with a twist
+            MenuItem::submenu(
+                "Variants",
+                vec![
+                    MenuItem::action(MenuAction::PlayTimed, "Timed match"),
+                    MenuItem::action(MenuAction::PlayMultiBall, "Multi-ball"),
+                    MenuItem::action(MenuAction::PlayFreeForAll, "Free-for-all"),
+                    MenuItem::action(MenuAction::PlayDoubles, "Doubles"),
+                    MenuItem::action(MenuAction::PlayAirHockey, "Air hockey"),
+                    MenuItem::action(MenuAction::PlayBreakout, "Breakout"),
+                ],
+            ),
+            MenuItem::action(MenuAction::Tournament, "Tournament"),
+            MenuItem::choice(
+                MenuAction::Difficulty,
+                "Difficulty",
+                &difficulty_names,
+                difficulty_index,
+            ),
+        ]
+    }
+
+    fn start(&mut self, world: &mut World, mode: GameMode) {
+        world.set_mode(mode);
+        self.is_done = true;
+    }
+
+    fn options_items(world: &World) -> Vec<MenuItem<MenuAction>> {
+        let settings = &world.settings;
+
+        let color_names: Vec<&str> = ParticleColor::ALL.iter().map(|c| c.name()).collect();
+        let color_index = ParticleColor::ALL
+            .iter()
+            .position(|c| *c == settings.particle_color)
+            .unwrap_or(0);
+        let lifetime_names: Vec<&str> = LIFETIMES.iter().map(|(name, _)| *name).collect();
+        let lifetime_index = LIFETIMES
+            .iter()
+            .position(|(_, lifetime)| *lifetime == settings.particle_lifetime)
+            .unwrap_or(1);
+
+        let scoring_names: Vec<&str> = ScoringKind::ALL.iter().map(|k| k.name()).collect();
+        let scoring_index = ScoringKind::ALL
+            .iter()
+            .position(|k| *k == settings.scoring)
+            .unwrap_or(0);
+        let best_of_names: Vec<&str> = BEST_OF.iter().map(|(name, _)| *name).collect();
+        let best_of_index = BEST_OF
+            .iter()
+            .position(|(_, best_of)| *best_of == settings.best_of)
+            .unwrap_or(0);
+        let time_limit_names: Vec<&str> = TIME_LIMITS.iter().map(|(name, _)| *name).collect();
+        let time_limit_index = TIME_LIMITS
+            .iter()
+            .position(|(_, minutes)| *minutes == settings.time_limit)
+            .unwrap_or(2);
+        let launch_names: Vec<String> = BallLaunch::ALL.iter().map(|l| l.name()).collect();
+        let launch_names: Vec<&str> = launch_names.iter().map(String::as_str).collect();
+        let launch_index = BallLaunch::ALL
+            .iter()
+            .position(|l| *l == settings.ball_launch)
+            .unwrap_or(1);
+
+        vec![
+            MenuItem::submenu(
+                "Match rules",
+                vec![
+                    MenuItem::slider(
+                        MenuAction::MaxScore,
+                        "Max score",
+                        world.get_max_score(),
+                        1,
+                        21,
+                        1,
+                    ),
+                    MenuItem::choice(
+                        MenuAction::Scoring,
+                        "Scoring",
+                        &scoring_names,
+                        scoring_index,
+                    ),
+                    MenuItem::choice(MenuAction::BestOf, "Sets", &best_of_names, best_of_index),
+                    MenuItem::choice(
+                        MenuAction::TimeLimit,
+                        "Time limit",
+                        &time_limit_names,
+                        time_limit_index,
+                    ),
+                    MenuItem::toggle(
+                        MenuAction::SuddenDeathSpeedup,
+                        "Sudden death speed-up",
+                        settings.sudden_death_speedup,
+                    ),
+                    MenuItem::choice(
+                        MenuAction::BallLaunch,
+                        "Extra ball",
+                        &launch_names,
+                        launch_index,
+                    ),
+                ],
+            ),
+            MenuItem::submenu(
+                "Power-ups",
+                vec![
+                    MenuItem::toggle(MenuAction::PowerUps, "Power-ups", settings.power_ups),
+                    MenuItem::submenu(
+                        "Paddle items",
+                        Self::power_up_weight_items(
+                            world,
+                            &[
+                                PowerUpKind::Grow,
+                                PowerUpKind::Shrink,
+                                PowerUpKind::Sticky,
+                                PowerUpKind::Reverse,
+                                PowerUpKind::Shield,
+                            ],
+                        ),
+                    ),
+                    MenuItem::submenu(
+                        "Ball items",
+                        Self::power_up_weight_items(
+                            world,
+                            &[
+                                PowerUpKind::FastBall,
+                                PowerUpKind::SlowBall,
+                                PowerUpKind::MultiBall,
+                            ],
+                        ),
+                    ),
+                ],
+            ),
+            MenuItem::toggle(MenuAction::Fullscreen, "Fullscreen", settings.fullscreen),
+            MenuItem::submenu(
+                "Sound",
+                vec![
+                    MenuItem::slider(
+                        MenuAction::Volume,
+                        "Volume",
+                        settings.volume,
+                        0,
+                        MAX_VOLUME,
+                        1,
+                    ),
+                    MenuItem::toggle(MenuAction::Mute, "Mute", settings.muted),
+                ],
+            ),
+            MenuItem::submenu(
+                "Effects",
+                vec![
+                    MenuItem::slider(
+                        MenuAction::ParticleCount,
+                        "Particles",
+                        settings.particle_count,
+                        0,
+                        MAX_PARTICLE_COUNT,
+                        4,
+                    ),
+                    MenuItem::choice(
+                        MenuAction::ParticleColor,
+                        "Colour",
+                        &color_names,
+                        color_index,
+                    ),
+                    MenuItem::choice(
+                        MenuAction::ParticleLifetime,
+                        "Lifetime",
+                        &lifetime_names,
+                        lifetime_index,
+                    ),
+                    MenuItem::toggle(
+                        MenuAction::ReducedEffects,
+                        "Reduced effects",
+                        settings.reduced_effects,
+                    ),
+                ],
+            ),
+            MenuItem::submenu(
+                "Game feel",
+                vec![
+                    MenuItem::toggle(
+                        MenuAction::ScreenShake,
+                        "Screen shake",
+                        settings.screen_shake,
+                    ),
+                    MenuItem::toggle(MenuAction::HitStop, "Hit-stop", settings.hit_stop),
+                    MenuItem::toggle(MenuAction::BallTrail, "Ball trail", settings.ball_trail),
+                    MenuItem::toggle(
+                        MenuAction::SquashStretch,
+                        "Squash & stretch",
+                        settings.squash_stretch,
+                    ),
+                    MenuItem::action(MenuAction::ClassicPreset, "Classic"),
+                    MenuItem::action(MenuAction::JuicyPreset, "Juicy"),
+                ],
+            ),
+        ]
+    }
+
+    // Spawn weight sliders, an item with weight 0 never shows up
+    fn power_up_weight_items(world: &World, kinds: &[PowerUpKind]) -> Vec<MenuItem<MenuAction>> {
+        kinds
             .iter()
-            .map(|title| {
-                graphics::Text::new(
-                    graphics::TextFragment::new(*title)
-                        .color(silver)
-                        .font(font)
-                        .scale(graphics::Scale::uniform(36.0)),
+            .map(|kind| {
+                MenuItem::slider(
+                    MenuAction::PowerUpWeight(*kind),
+                    kind.name(),
+                    world.settings.power_up_weights[kind.index()],
+                    0,
+                    MAX_POWER_UP_WEIGHT,
+                    1,
                 )
             })
-            .collect();
+            .collect()
+    }
 
-        let paddle_size = (6., 20.);
-        let paddle_speed = 0.;
+    // Presets flip every game feel option at once and keep the toggles in sync
+    fn apply_juice_preset(&mut self, world: &mut World, enabled: bool) {
+        world.settings.set_juice(enabled);
+        for id in &[
+            MenuAction::ScreenShake,
+            MenuAction::HitStop,
+            MenuAction::BallTrail,
+            MenuAction::SquashStretch,
+        ] {
+            self.menu.set_toggle_value(*id, enabled);
+        }
+    }
 
-        let left_indicator_paddle = Paddle::new(
-            ctx,
-            na::Vector2::new(0., 0.),
-            graphics::Rect::new(0., 0., paddle_size.0, paddle_size.1),
-            paddle_speed,
-        );
-        let right_indicator_paddle = Paddle::new(
-            ctx,
-            na::Vector2::new(0., 0.),
-            graphics::Rect::new(0., 0., paddle_size.0, paddle_size.1),
-            paddle_speed,
-        );
+    fn handle_event(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        event: Option<MenuEvent<MenuAction>>,
+    ) {
+        let feedback_sound = event.as_ref().map(|event| event.feedback_sound());
 
-        Self {
-            title_text: graphics::Text::new(title_text_fragment),
-            is_done: false,
-            selected_item_index: 0,
-            menu_items: items,
-            silver_color: silver,
-            left_indicator_paddle,
-            right_indicator_paddle,
+        match event {
+            Some(MenuEvent::Activated(MenuAction::PlayVersus)) => {
+                self.start(world, GameMode::Versus)
+            }
+            Some(MenuEvent::Activated(MenuAction::PlayVersusAi)) => {
+                let difficulty = world.settings.difficulty;
+                self.start(world, GameMode::VersusAi(difficulty))
+            }
+            Some(MenuEvent::Activated(MenuAction::PlaySurvival)) => {
+                let difficulty = world.settings.difficulty;
+                self.start(world, GameMode::Survival(difficulty))
+            }
+            Some(MenuEvent::Activated(MenuAction::PlayTimed)) => self.start(world, GameMode::Timed),
+            Some(MenuEvent::Activated(MenuAction::PlayMultiBall)) => {
+                self.start(world, GameMode::MultiBall)
+            }
+            Some(MenuEvent::Activated(MenuAction::PlayFreeForAll)) => {
+                self.start(world, GameMode::FreeForAll)
+            }
+            Some(MenuEvent::Activated(MenuAction::PlayDoubles)) => {
+                self.start(world, GameMode::Doubles)
+            }
+            Some(MenuEvent::Activated(MenuAction::PlayAirHockey)) => {
+                self.start(world, GameMode::AirHockey)
+            }
+            Some(MenuEvent::Activated(MenuAction::PlayBreakout)) => {
+                self.start(world, GameMode::Breakout)
+            }
+            Some(MenuEvent::Activated(MenuAction::PlayPractice)) => {
+                let difficulty = world.settings.difficulty;
+                self.start(world, GameMode::Practice(difficulty))
+            }
+            Some(MenuEvent::Activated(MenuAction::Tournament)) => self.is_tournament = true,
+            Some(MenuEvent::Activated(MenuAction::Editor)) => self.is_editor = true,
+            Some(MenuEvent::Changed(MenuAction::Difficulty)) => {
+                if let Some(index) = self.menu.choice_index(MenuAction::Difficulty) {
+                    world.settings.difficulty = Difficulty::ALL[index];
+                }
+            }
+            Some(MenuEvent::Activated(MenuAction::Leaderboard)) => self.is_leaderboard = true,
+            Some(MenuEvent::Activated(MenuAction::HighScores)) => self.is_high_scores = true,
+            Some(MenuEvent::Activated(MenuAction::About)) => self.is_about = true,
+            Some(MenuEvent::Activated(MenuAction::Exit)) => event::quit(ctx),
+            Some(MenuEvent::Changed(MenuAction::MaxScore)) => {
+                if let Some(max_score) = self.menu.slider_value(MenuAction::MaxScore) {
+                    world.set_max_score(max_score);
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::Scoring)) => {
+                if let Some(index) = self.menu.choice_index(MenuAction::Scoring) {
+                    world.settings.scoring = ScoringKind::ALL[index];
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::BestOf)) => {
+                if let Some(index) = self.menu.choice_index(MenuAction::BestOf) {
+                    world.settings.best_of = BEST_OF[index].1;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::TimeLimit)) => {
+                if let Some(index) = self.menu.choice_index(MenuAction::TimeLimit) {
+                    world.settings.time_limit = TIME_LIMITS[index].1;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::SuddenDeathSpeedup)) => {
+                if let Some(speedup) = self.menu.toggle_value(MenuAction::SuddenDeathSpeedup) {
+                    world.settings.sudden_death_speedup = speedup;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::BallLaunch)) => {
+                if let Some(index) = self.menu.choice_index(MenuAction::BallLaunch) {
+                    world.settings.ball_launch = BallLaunch::ALL[index];
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::PowerUps)) => {
+                if let Some(power_ups) = self.menu.toggle_value(MenuAction::PowerUps) {
+                    world.settings.power_ups = power_ups;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::PowerUpWeight(kind))) => {
+                if let Some(weight) = self.menu.slider_value(MenuAction::PowerUpWeight(kind)) {
+                    world.settings.power_up_weights[kind.index()] = weight;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::Fullscreen)) => {
+                if let Some(fullscreen) = self.menu.toggle_value(MenuAction::Fullscreen) {
+                    // The platform can refuse the switch, keep the window as it was
+                    if let Err(error) = world.settings.set_fullscreen(ctx, fullscreen) {
+                        eprintln!("Could not change fullscreen mode: {}", error);
+                        world.settings.fullscreen = !fullscreen;
+                        self.menu
+                            .set_toggle_value(MenuAction::Fullscreen, !fullscreen);
+                    }
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::Volume)) => {
+                if let Some(volume) = self.menu.slider_value(MenuAction::Volume) {
+                    world.settings.volume = volume;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::Mute)) => {
+                if let Some(muted) = self.menu.toggle_value(MenuAction::Mute) {
+                    world.settings.muted = muted;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::ParticleCount)) => {
+                if let Some(count) = self.menu.slider_value(MenuAction::ParticleCount) {
+                    world.settings.particle_count = count;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::ParticleColor)) => {
+                if let Some(index) = self.menu.choice_index(MenuAction::ParticleColor) {
+                    world.settings.particle_color = ParticleColor::ALL[index];
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::ParticleLifetime)) => {
+                if let Some(index) = self.menu.choice_index(MenuAction::ParticleLifetime) {
+                    world.settings.particle_lifetime = LIFETIMES[index].1;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::ReducedEffects)) => {
+                if let Some(reduced) = self.menu.toggle_value(MenuAction::ReducedEffects) {
+                    world.settings.reduced_effects = reduced;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::ScreenShake)) => {
+                if let Some(shake) = self.menu.toggle_value(MenuAction::ScreenShake) {
+                    world.settings.screen_shake = shake;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::HitStop)) => {
+                if let Some(hit_stop) = self.menu.toggle_value(MenuAction::HitStop) {
+                    world.settings.hit_stop = hit_stop;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::BallTrail)) => {
+                if let Some(trail) = self.menu.toggle_value(MenuAction::BallTrail) {
+                    world.settings.ball_trail = trail;
+                }
+            }
+            Some(MenuEvent::Changed(MenuAction::SquashStretch)) => {
+                if let Some(squash) = self.menu.toggle_value(MenuAction::SquashStretch) {
+                    world.settings.squash_stretch = squash;
+                }
+            }
+            Some(MenuEvent::Activated(MenuAction::ClassicPreset)) => {
+                self.apply_juice_preset(world, false)
+            }
+            Some(MenuEvent::Activated(MenuAction::JuicyPreset)) => {
+                self.apply_juice_preset(world, true)
+            }
+            _ => {}
+        };
+
+        if let Some(sound) = feedback_sound {
+            world.play_sound(ctx, sound);
         }
     }
 }
 
 impl Scene<World> for MenuScene {
-    fn update(&mut self, ctx: &mut Context, _world: &mut World) -> Option<Box<dyn Scene<World>>> {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
         if self.is_done {
-            let next_scene = gs::new(ctx);
+            let next_scene = ss::new(ctx, world);
             Some(Box::new(next_scene))
+        } else if self.is_leaderboard {
+            Some(Box::new(lbs::new(ctx, world)))
+        } else if self.is_tournament {
+            Some(Box::new(ts::new(ctx, world)))
+        } else if self.is_editor {
+            Some(Box::new(eds::new(ctx)))
+        } else if self.is_high_scores {
+            Some(Box::new(sbs::new(ctx, world)))
+        } else if self.is_about {
+            Some(Box::new(abs::new(ctx, world)))
         } else {
             None
         }
     }
 
     fn draw(&mut self, ctx: &mut Context, _world: &mut World) {
-        let (scr_width, scr_height) = graphics::drawable_size(ctx);
+        let (scr_width, scr_height) = (ARENA_WIDTH, ARENA_HEIGHT);
 
         let title_text_width = self.title_text.width(ctx) as f32;
         let title_text_height = self.title_text.height(ctx) as f32;
 
         let title_text_dest = na::Point2::new(
-            (scr_width as f32 / 2.0) - (title_text_width / 2.0),
-            (scr_height as f32 / 2.0) - (title_text_height + 20.0),
+            (scr_width / 2.0) - (title_text_width / 2.0),
+            (scr_height / 4.0) - (title_text_height + 20.0),
         );
 
         graphics::draw(
@@ -93,82 +555,43 @@ impl Scene<World> for MenuScene {
         )
         .unwrap();
 
-        for (index, item) in self.menu_items.iter_mut().enumerate() {
-            let width = item.width(ctx) as f32;
-            let height = item.height(ctx) as f32;
-
-            let dest = na::Point2::new(
-                (scr_width as f32 / 2.0) - (title_text_width as f32 / 2.0),
-                (scr_height as f32 / 2.0)
-                    + ((title_text_height + 20.0) - (height + 20.0))
-                    + (index as f32 * 50.),
-            );
-
-            let item_height_half = height * 0.5;
-
-            if index as i32 == self.selected_item_index {
-                item.fragments_mut()[0].color = Some(graphics::WHITE);
-
-                self.left_indicator_paddle.set_position(na::Point2::new(
-                    dest.x - item_height_half,
-                    dest.y + item_height_half - (self.left_indicator_paddle.size.1 * 0.5),
-                ));
-
-                self.right_indicator_paddle.set_position(na::Point2::new(
-                    dest.x + width + item_height_half - self.right_indicator_paddle.size.0,
-                    dest.y + item_height_half - (self.right_indicator_paddle.size.1 * 0.5),
-                ));
-
-                graphics::draw(
-                    ctx,
-                    self.left_indicator_paddle.get_mesh(),
-                    graphics::DrawParam::default().dest(self.left_indicator_paddle.get_position()),
-                )
-                .unwrap();
-                graphics::draw(
-                    ctx,
-                    self.right_indicator_paddle.get_mesh(),
-                    graphics::DrawParam::default().dest(self.right_indicator_paddle.get_position()),
-                )
-                .unwrap();
-            } else {
-                item.fragments_mut()[0].color = Some(self.silver_color);
-            }
-
-            graphics::draw(ctx, item, graphics::DrawParam::new().dest(dest)).unwrap();
-        }
+        self.menu.draw(
+            ctx,
+            na::Point2::new(title_text_dest.x, (scr_height / 4.0) + 20.0),
+        );
     }
 
     fn input(
         &mut self,
         ctx: &mut Context,
-        _world: &mut World,
+        world: &mut World,
         keycode: KeyCode,
         _pressed: bool,
         _repeat: bool,
     ) {
-        match keycode {
-            KeyCode::Space | KeyCode::Return | KeyCode::D | KeyCode::Right => {
-                if self.selected_item_index == 0 {
-                    self.is_done = true;
-                } else {
-                    event::quit(ctx);
-                }
-            }
-            KeyCode::Up | KeyCode::W => {
-                let item_count = self.menu_items.len() as i32;
-                self.selected_item_index = if self.selected_item_index == 0 {
-                    item_count - 1
-                } else {
-                    (self.selected_item_index - 1) % item_count
-                };
-            }
-            KeyCode::Down | KeyCode::S => {
-                let item_count = self.menu_items.len() as i32;
-                self.selected_item_index = (self.selected_item_index + 1) % item_count;
-            }
-            _ => {}
+        let event = self.menu.input(keycode);
+        self.handle_event(ctx, world, event);
+    }
+
+    fn mouse_motion(&mut self, ctx: &mut Context, world: &mut World, x: f32, y: f32) {
+        let event = self.menu.mouse_motion(x, y);
+        self.handle_event(ctx, world, event);
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) {
+        let event = match button {
+            MouseButton::Left => self.menu.mouse_click(x, y),
+            MouseButton::Right => self.menu.input(KeyCode::Back),
+            _ => None,
         };
+        self.handle_event(ctx, world, event);
     }
 
     fn name(&self) -> &str {
@@ -1,8 +1,17 @@
+pub mod about;
+pub mod editor;
 pub mod end;
 pub mod game;
+pub mod leaderboard;
 pub mod menu;
+pub mod scoreboard;
+pub mod setup;
+pub mod tournament;
 
-use crate::{Context, KeyCode};
+use crate::{
+    viewport::{ARENA_HEIGHT, ARENA_WIDTH},
+    Context, KeyCode, MouseButton,
+};
 
 pub trait Scene<W> {
     fn update(&mut self, ctx: &mut Context, world: &mut W) -> Option<Box<dyn Scene<W>>>;
@@ -15,5 +24,29 @@ pub trait Scene<W> {
         pressed: bool,
         repeat: bool,
     );
+    fn text_input(&mut self, _ctx: &mut Context, _world: &mut W, _character: char) {}
+    fn mouse_motion(&mut self, _ctx: &mut Context, _world: &mut W, _x: f32, _y: f32) {}
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut W,
+        _button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) {
+    }
+    fn mouse_button_up(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut W,
+        _button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) {
+    }
+    // Logical size the window is fitted to while the scene is shown
+    fn arena_size(&self, _world: &W) -> (f32, f32) {
+        (ARENA_WIDTH, ARENA_HEIGHT)
+    }
     fn name(&self) -> &str;
 }
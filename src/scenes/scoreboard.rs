@@ -0,0 +1,243 @@
+use crate::{
+    end::EndScene as es,
+    graphics,
+    highscores::TABLE_SIZE,
+    menu::MenuScene as ms,
+    mode::{Difficulty, GameMode},
+    na,
+    profile::MAX_NAME_LENGTH,
+    ui::text_input::{TextInput, TextInputEvent},
+    viewport::ARENA_WIDTH,
+    Context, DrawParam, KeyCode, MouseButton, Scene, World,
+};
+
+const TABLE_TOP: f32 = 160.;
+const ROW_HEIGHT: f32 = 30.;
+
+enum Stage {
+    // A new record was set, type the name to store it under
+    NameEntry(i32),
+    View,
+}
+
+// Top scores of the solo modes, also shown after a new record is set
+pub struct ScoreboardScene {
+    title_text: graphics::Text,
+    font: graphics::Font,
+
+    stage: Stage,
+    name_input: TextInput,
+    // Every mode and difficulty with a table, the one at `current` is shown
+    modes: Vec<GameMode>,
+    current: usize,
+    // Rank of the record just entered
+    new_rank: Option<usize>,
+    // Winner of the match that set the record, the end screen follows
+    winner: Option<String>,
+
+    is_done: bool,
+}
+
+impl ScoreboardScene {
+    pub fn new(ctx: &mut Context, world: &World) -> Self {
+        let modes: Vec<GameMode> = Difficulty::ALL
+            .iter()
+            .flat_map(|difficulty| GameMode::solo_modes(*difficulty))
+            .collect();
+        let current = modes
+            .iter()
+            .position(|mode| *mode == world.get_mode())
+            .unwrap_or(0);
+        let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
+
+        Self {
+            title_text: Self::title(font, "High scores"),
+            font,
+            stage: Stage::View,
+            name_input: TextInput::new(ctx, MAX_NAME_LENGTH),
+            modes,
+            current,
+            new_rank: None,
+            winner: None,
+            is_done: false,
+        }
+    }
+
+    // Ask for a name for the record just set in the world's current mode
+    pub fn new_record(ctx: &mut Context, world: &World, score: i32, winner: String) -> Self {
+        let mut scene = Self::new(ctx, world);
+        scene.title_text = Self::title(scene.font, "New high score!");
+        scene.stage = Stage::NameEntry(score);
+        scene.name_input =
+            TextInput::new(ctx, MAX_NAME_LENGTH).with_text(world.player(0).get_name());
+        scene.winner = Some(winner);
+        scene
+    }
+
+    fn title(font: graphics::Font, title: &str) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(title)
+                .color(graphics::Color::from((192, 128, 64, 255)))
+                .font(font)
+                .scale(graphics::Scale::uniform(48.0)),
+        )
+    }
+
+    fn text(&self, text: String, color: graphics::Color) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(text)
+                .color(color)
+                .font(self.font)
+                .scale(graphics::Scale::uniform(24.0)),
+        )
+    }
+
+    fn draw_centered(&self, ctx: &mut Context, text: &graphics::Text, y: f32) {
+        let x = (ARENA_WIDTH - text.width(ctx) as f32) * 0.5;
+        graphics::draw(ctx, text, DrawParam::new().dest(na::Point2::new(x, y))).unwrap();
+    }
+
+    fn cycle(&mut self, step: isize) {
+        let count = self.modes.len() as isize;
+        self.current = (self.current as isize + step).rem_euclid(count) as usize;
+        self.new_rank = None;
+    }
+
+    fn handle_name_entry(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        score: i32,
+        event: Option<TextInputEvent>,
+    ) {
+        match event {
+            Some(TextInputEvent::Submitted(name)) => {
+                self.new_rank = world.high_scores.insert(world.get_mode(), &name, score);
+                world.high_scores.save(ctx);
+                self.title_text = Self::title(self.font, "High scores");
+                self.stage = Stage::View;
+            }
+            // Skip the record without saving it
+            Some(TextInputEvent::Cancelled) => self.is_done = true,
+            None => {}
+        }
+    }
+}
+
+impl Scene<World> for ScoreboardScene {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
+        if !self.is_done {
+            return None;
+        }
+
+        match self.winner.take() {
+            Some(winner) => Some(Box::new(es::new(ctx, winner, world))),
+            None => Some(Box::new(ms::new(ctx, world))),
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, world: &mut World) {
+        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
+        let highlight = graphics::Color::from((192, 128, 64, 255));
+
+        self.draw_centered(ctx, &self.title_text, 30.);
+
+        let mode = self.modes[self.current];
+        if let Stage::NameEntry(score) = self.stage {
+            let score_text = self.text(format!("{}: {}", mode.describe(), score), silver);
+            self.draw_centered(ctx, &score_text, TABLE_TOP);
+            let prompt_text = self.text(String::from("Enter your name"), silver);
+            self.draw_centered(ctx, &prompt_text, TABLE_TOP + ROW_HEIGHT * 2.);
+            self.name_input.draw(
+                ctx,
+                na::Point2::new(ARENA_WIDTH * 0.3, TABLE_TOP + ROW_HEIGHT * 3.5),
+            );
+            return;
+        }
+
+        let heading_text = self.text(format!("<  {}  >", mode.describe()), highlight);
+        self.draw_centered(ctx, &heading_text, TABLE_TOP - ROW_HEIGHT * 2.);
+
+        let entries = world.high_scores.entries(mode);
+        if entries.is_empty() {
+            let empty_text = self.text(String::from("No scores yet"), silver);
+            self.draw_centered(ctx, &empty_text, TABLE_TOP);
+            return;
+        }
+
+        for (rank, entry) in entries.iter().take(TABLE_SIZE).enumerate() {
+            let color = if self.new_rank == Some(rank) {
+                highlight
+            } else {
+                silver
+            };
+            let y = TABLE_TOP + rank as f32 * ROW_HEIGHT;
+            let cells = [
+                (ARENA_WIDTH * 0.25, format!("{}.", rank + 1)),
+                (ARENA_WIDTH * 0.32, entry.name.clone()),
+                (ARENA_WIDTH * 0.7, entry.score.to_string()),
+            ];
+            for (x, cell) in cells.iter() {
+                let cell_text = self.text(cell.clone(), color);
+                graphics::draw(
+                    ctx,
+                    &cell_text,
+                    DrawParam::new().dest(na::Point2::new(*x, y)),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn input(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        keycode: KeyCode,
+        _pressed: bool,
+        _repeat: bool,
+    ) {
+        if let Stage::NameEntry(score) = self.stage {
+            let event = self.name_input.input(keycode);
+            self.handle_name_entry(ctx, world, score, event);
+            return;
+        }
+
+        match keycode {
+            KeyCode::Back | KeyCode::Return | KeyCode::Space => self.is_done = true,
+            KeyCode::Left | KeyCode::A => self.cycle(-1),
+            KeyCode::Right | KeyCode::D => self.cycle(1),
+            _ => {}
+        };
+    }
+
+    fn text_input(&mut self, _ctx: &mut Context, _world: &mut World, character: char) {
+        if let Stage::NameEntry(_) = self.stage {
+            self.name_input.text_input(character);
+        }
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _world: &mut World,
+        button: MouseButton,
+        x: f32,
+        _y: f32,
+    ) {
+        if let Stage::NameEntry(_) = self.stage {
+            return;
+        }
+
+        match button {
+            MouseButton::Left if x < ARENA_WIDTH * 0.5 => self.cycle(-1),
+            MouseButton::Left => self.cycle(1),
+            MouseButton::Right => self.is_done = true,
+            _ => {}
+        };
+    }
+
+    fn name(&self) -> &str {
+        "Scoreboard Scene"
+    }
+}
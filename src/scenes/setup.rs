@@ -0,0 +1,414 @@
+use crate::{
+    arena::Arena,
+    game::GameScene as gs,
+    graphics,
+    menu::MenuScene as ms,
+    mode::Difficulty,
+    na,
+    profile::{PaddleColor, MAX_NAME_LENGTH},
+    ui::{
+        menu::{Menu, MenuEvent, MenuItem},
+        text_input::{TextInput, TextInputEvent},
+    },
+    viewport::{ARENA_HEIGHT, ARENA_WIDTH},
+    world::Seat,
+    Context, ControlMode, KeyCode, MouseButton, Scene, World,
+};
+
+const CONTROL_MODES: [ControlMode; 3] = [
+    ControlMode::Keyboard,
+    ControlMode::Mouse,
+    ControlMode::Gamepad,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum ProfileAction {
+    Select(usize),
+    New,
+    Computer(Difficulty),
+    Controls,
+    Color,
+    Continue,
+    Arena(usize),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Stage {
+    // Pick a profile for the player at this index, or the computer in doubles
+    Choose(usize),
+    // Edit the preferences of the chosen profile
+    Preferences(usize),
+    // Type the name of a new profile
+    NameEntry(usize),
+    // Pick the arena the match is played on
+    Arena,
+}
+
+// Choose or create the local profiles of every player before a match
+pub struct SetupScene {
+    title_text: graphics::Text,
+
+    is_done: bool,
+    is_started: bool,
+
+    stage: Stage,
+    // Who plays on each seat, player 1 first
+    chosen: Vec<Seat>,
+
+    menu: Menu<ProfileAction>,
+    name_input: TextInput,
+    arenas: Vec<Arena>,
+}
+
+impl SetupScene {
+    pub fn new(ctx: &mut Context, world: &World) -> Self {
+        let stage = Stage::Choose(0);
+        let chosen: Vec<Seat> = (0..world.get_mode().player_count())
+            .map(Seat::Profile)
+            .collect();
+        let arenas = Arena::load_all(ctx);
+
+        Self {
+            title_text: Self::title(ctx, world, stage, &chosen),
+            is_done: false,
+            is_started: false,
+            stage,
+            menu: Self::build_menu(ctx, world, stage, &chosen, &arenas),
+            chosen,
+            name_input: TextInput::new(ctx, MAX_NAME_LENGTH),
+            arenas,
+        }
+    }
+
+    fn title(ctx: &mut Context, world: &World, stage: Stage, chosen: &[Seat]) -> graphics::Text {
+        let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
+        let title = match stage {
+            Stage::Choose(player) => format!("Player {}", player + 1),
+            Stage::Preferences(player) => match chosen[player] {
+                Seat::Profile(profile) => world.profiles.profiles[profile].name.clone(),
+                Seat::Computer(_) => String::from("Computer"),
+            },
+            Stage::NameEntry(player) => format!("Player {} name", player + 1),
+            Stage::Arena => String::from("Arena"),
+        };
+
+        graphics::Text::new(
+            graphics::TextFragment::new(title)
+                .color(graphics::Color::from((192, 128, 64, 255)))
+                .font(font)
+                .scale(graphics::Scale::uniform(56.0)),
+        )
+    }
+
+    fn build_menu(
+        ctx: &mut Context,
+        world: &World,
+        stage: Stage,
+        chosen: &[Seat],
+        arenas: &[Arena],
+    ) -> Menu<ProfileAction> {
+        let items = match stage {
+            Stage::Arena => arenas
+                .iter()
+                .enumerate()
+                .map(|(index, arena)| {
+                    let label = format!("{} ({}x{})", arena.name, arena.width, arena.height);
+                    MenuItem::action(ProfileAction::Arena(index), &label)
+                })
+                .collect(),
+            Stage::Preferences(player) => {
+                let profile = &world.profiles.profiles[Self::profile_index(chosen, player)];
+                let controls_index = CONTROL_MODES
+                    .iter()
+                    .position(|mode| *mode == profile.controls)
+                    .unwrap_or(0);
+                let color_index = PaddleColor::ALL
+                    .iter()
+                    .position(|color| *color == profile.color)
+                    .unwrap_or(0);
+                let color_names: Vec<&str> =
+                    PaddleColor::ALL.iter().map(|color| color.name()).collect();
+
+                vec![
+                    MenuItem::choice(
+                        ProfileAction::Controls,
+                        "Controls",
+                        &["Keyboard", "Mouse", "Gamepad"],
+                        controls_index,
+                    ),
+                    MenuItem::choice(ProfileAction::Color, "Colour", &color_names, color_index),
+                    MenuItem::action(ProfileAction::Continue, "Continue"),
+                ]
+            }
+            _ => {
+                let mut items: Vec<MenuItem<ProfileAction>> = world
+                    .profiles
+                    .profiles
+                    .iter()
+                    .enumerate()
+                    .map(|(index, profile)| {
+                        let item = MenuItem::action(ProfileAction::Select(index), &profile.name);
+                        // A player can't pick a profile an earlier player already has
+                        let is_taken = match stage {
+                            Stage::Choose(player) => {
+                                chosen[..player].contains(&Seat::Profile(index))
+                            }
+                            _ => false,
+                        };
+                        if is_taken {
+                            item.disabled()
+                        } else {
+                            item
+                        }
+                    })
+                    .collect();
+                items.push(MenuItem::action(ProfileAction::New, "New profile"));
+                if world.get_mode().has_computer_seats() {
+                    items.extend(Difficulty::ALL.iter().map(|difficulty| {
+                        let label = format!("Computer ({})", difficulty.name());
+                        MenuItem::action(ProfileAction::Computer(*difficulty), &label)
+                    }));
+                }
+                items
+            }
+        };
+
+        Menu::new(ctx, items)
+    }
+
+    // Profile of a seat that's been given to a person
+    fn profile_index(chosen: &[Seat], player: usize) -> usize {
+        match chosen[player] {
+            Seat::Profile(profile) => profile,
+            Seat::Computer(_) => unreachable!("the computer has no preferences"),
+        }
+    }
+
+    // Move on to the next seat, starting it on a profile no one has picked yet,
+    // or to the arena once every seat is taken
+    fn next_seat(&mut self, ctx: &mut Context, world: &World, player: usize) {
+        let next = player + 1;
+        if next >= self.chosen.len() {
+            self.set_stage(ctx, world, Stage::Arena);
+            return;
+        }
+
+        let is_taken = |chosen: &[Seat], seat: Seat| match seat {
+            Seat::Profile(_) => chosen.contains(&seat),
+            Seat::Computer(_) => false,
+        };
+        if is_taken(&self.chosen[..next], self.chosen[next]) {
+            if let Some(free) = (0..world.profiles.profiles.len())
+                .map(Seat::Profile)
+                .find(|seat| !is_taken(&self.chosen[..next], *seat))
+            {
+                self.chosen[next] = free;
+            }
+        }
+        self.set_stage(ctx, world, Stage::Choose(next));
+    }
+
+    fn set_stage(&mut self, ctx: &mut Context, world: &World, stage: Stage) {
+        self.stage = stage;
+        self.title_text = Self::title(ctx, world, stage, &self.chosen);
+        self.menu = Self::build_menu(ctx, world, stage, &self.chosen, &self.arenas);
+        if let Stage::NameEntry(_) = stage {
+            self.name_input = TextInput::new(ctx, MAX_NAME_LENGTH);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        event: Option<MenuEvent<ProfileAction>>,
+    ) {
+        let feedback_sound = event.as_ref().map(|event| event.feedback_sound());
+
+        match (self.stage, event) {
+            (Stage::Choose(player), Some(MenuEvent::Activated(ProfileAction::Select(index)))) => {
+                self.chosen[player] = Seat::Profile(index);
+                self.set_stage(ctx, world, Stage::Preferences(player));
+            }
+            (Stage::Choose(player), Some(MenuEvent::Activated(ProfileAction::New))) => {
+                self.set_stage(ctx, world, Stage::NameEntry(player));
+            }
+            (
+                Stage::Choose(player),
+                Some(MenuEvent::Activated(ProfileAction::Computer(difficulty))),
+            ) => {
+                self.chosen[player] = Seat::Computer(difficulty);
+                self.next_seat(ctx, world, player);
+            }
+            (Stage::Choose(0), Some(MenuEvent::Back)) => self.is_done = true,
+            (Stage::Choose(player), Some(MenuEvent::Back)) => {
+                self.set_stage(ctx, world, Stage::Choose(player - 1));
+            }
+            (Stage::Preferences(player), Some(MenuEvent::Changed(ProfileAction::Controls))) => {
+                if let Some(index) = self.menu.choice_index(ProfileAction::Controls) {
+                    let profile = Self::profile_index(&self.chosen, player);
+                    world.profiles.profiles[profile].controls = CONTROL_MODES[index];
+                    world.profiles.save(ctx);
+                }
+            }
+            (Stage::Preferences(player), Some(MenuEvent::Changed(ProfileAction::Color))) => {
+                if let Some(index) = self.menu.choice_index(ProfileAction::Color) {
+                    let profile = Self::profile_index(&self.chosen, player);
+                    world.profiles.profiles[profile].color = PaddleColor::ALL[index];
+                    world.profiles.save(ctx);
+                }
+            }
+            // Solo modes only need player 1, the computer is player 2
+            (Stage::Preferences(0), Some(MenuEvent::Activated(ProfileAction::Continue)))
+                if world.get_mode().is_solo() =>
+            {
+                self.set_stage(ctx, world, Stage::Arena);
+            }
+            (Stage::Preferences(player), Some(MenuEvent::Activated(ProfileAction::Continue))) => {
+                self.next_seat(ctx, world, player);
+            }
+            (Stage::Preferences(player), Some(MenuEvent::Back)) => {
+                self.set_stage(ctx, world, Stage::Choose(player));
+            }
+            (Stage::Arena, Some(MenuEvent::Activated(ProfileAction::Arena(index)))) => {
+                world.arena = self.arenas[index].clone();
+                self.is_started = true;
+            }
+            (Stage::Arena, Some(MenuEvent::Back)) => {
+                let player = if world.get_mode().is_solo() {
+                    0
+                } else {
+                    self.chosen.len() - 1
+                };
+                // The computer has no preferences, go back to picking the seat
+                let stage = match self.chosen[player] {
+                    Seat::Profile(_) => Stage::Preferences(player),
+                    Seat::Computer(_) => Stage::Choose(player),
+                };
+                self.set_stage(ctx, world, stage);
+            }
+            _ => {}
+        }
+
+        if let Some(sound) = feedback_sound {
+            world.play_sound(ctx, sound);
+        }
+    }
+
+    fn handle_name_entry(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        player: usize,
+        event: Option<TextInputEvent>,
+    ) {
+        match event {
+            Some(TextInputEvent::Submitted(name)) => {
+                let index = world.profiles.find_or_create(&name);
+                world.profiles.save(ctx);
+
+                // Two players can't share a profile, let them pick again
+                if self.chosen[..player].contains(&Seat::Profile(index)) {
+                    self.set_stage(ctx, world, Stage::Choose(player));
+                } else {
+                    self.chosen[player] = Seat::Profile(index);
+                    self.set_stage(ctx, world, Stage::Preferences(player));
+                }
+            }
+            Some(TextInputEvent::Cancelled) => self.set_stage(ctx, world, Stage::Choose(player)),
+            None => {}
+        }
+    }
+}
+
+impl Scene<World> for SetupScene {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
+        if self.is_done {
+            Some(Box::new(ms::new(ctx, world)))
+        } else if self.is_started {
+            world.set_lineup(&self.chosen);
+            world.reset(ctx);
+            Some(Box::new(gs::new(ctx)))
+        } else {
+            None
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _world: &mut World) {
+        let (scr_width, scr_height) = (ARENA_WIDTH, ARENA_HEIGHT);
+
+        let title_text_width = self.title_text.width(ctx) as f32;
+        let title_text_height = self.title_text.height(ctx) as f32;
+
+        let title_text_dest = na::Point2::new(
+            (scr_width / 2.0) - (title_text_width / 2.0),
+            (scr_height / 4.0) - (title_text_height + 20.0),
+        );
+
+        graphics::draw(
+            ctx,
+            &self.title_text,
+            graphics::DrawParam::new().dest(title_text_dest),
+        )
+        .unwrap();
+
+        let content_dest = na::Point2::new(title_text_dest.x, (scr_height / 4.0) + 20.0);
+        if let Stage::NameEntry(_) = self.stage {
+            self.name_input.draw(ctx, content_dest);
+        } else {
+            self.menu.draw(ctx, content_dest);
+        }
+    }
+
+    fn input(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        keycode: KeyCode,
+        _pressed: bool,
+        _repeat: bool,
+    ) {
+        if let Stage::NameEntry(player) = self.stage {
+            let event = self.name_input.input(keycode);
+            self.handle_name_entry(ctx, world, player, event);
+        } else {
+            let event = self.menu.input(keycode);
+            self.handle_event(ctx, world, event);
+        }
+    }
+
+    fn text_input(&mut self, _ctx: &mut Context, _world: &mut World, character: char) {
+        if let Stage::NameEntry(_) = self.stage {
+            self.name_input.text_input(character);
+        }
+    }
+
+    fn mouse_motion(&mut self, ctx: &mut Context, world: &mut World, x: f32, y: f32) {
+        let event = self.menu.mouse_motion(x, y);
+        self.handle_event(ctx, world, event);
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) {
+        if let Stage::NameEntry(_) = self.stage {
+            return;
+        }
+
+        let event = match button {
+            MouseButton::Left => self.menu.mouse_click(x, y),
+            MouseButton::Right => self.menu.input(KeyCode::Back),
+            _ => None,
+        };
+        self.handle_event(ctx, world, event);
+    }
+
+    fn name(&self) -> &str {
+        "Setup Scene"
+    }
+}
@@ -0,0 +1,521 @@
+use crate::{
+    arena::Arena,
+    bracket::{Bracket, Elimination, Tournament, TournamentMatch, MAX_ENTRANTS, MIN_ENTRANTS},
+    game::GameScene as gs,
+    graphics,
+    menu::MenuScene as ms,
+    mode::GameMode,
+    na,
+    profile::MAX_NAME_LENGTH,
+    ui::{
+        menu::{Menu, MenuEvent, MenuItem},
+        text_input::{TextInput, TextInputEvent},
+    },
+    viewport::{ARENA_HEIGHT, ARENA_WIDTH},
+    world::Seat,
+    Context, DrawParam, KeyCode, MouseButton, Scene, World,
+};
+
+const LIST_TOP: f32 = 110.;
+const ROW_HEIGHT: f32 = 24.;
+const COLUMN_WIDTH: f32 = 260.;
+const VISIBLE_ROUNDS: usize = 3;
+const MATCH_HEIGHT: f32 = 46.;
+
+#[derive(Clone, Copy, PartialEq)]
+enum TournamentAction {
+    Add,
+    RemoveLast,
+    Format,
+    Start,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Stage {
+    // Build the list of entrants and pick the format
+    Entrants,
+    // Type the profile name of a new entrant
+    NameEntry,
+    // Show the bracket between matches
+    Bracket,
+}
+
+pub struct TournamentScene {
+    title_text: graphics::Text,
+    font: graphics::Font,
+
+    stage: Stage,
+    entrants: Vec<String>,
+    elimination: Elimination,
+    // First round column shown in the bracket
+    first_round: usize,
+
+    menu: Menu<TournamentAction>,
+    name_input: TextInput,
+
+    is_done: bool,
+    is_started: bool,
+}
+
+impl TournamentScene {
+    // Resumes the tournament in progress, or sets up a new one
+    pub fn new(ctx: &mut Context, world: &mut World) -> Self {
+        if world.tournament.is_none() {
+            world.tournament = Tournament::load(ctx);
+        }
+
+        let stage = if world.tournament.is_some() {
+            Stage::Bracket
+        } else {
+            Stage::Entrants
+        };
+        let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
+        let entrants = Vec::new();
+        let elimination = Elimination::Single;
+
+        let mut scene = Self {
+            title_text: Self::title(font, ""),
+            font,
+            stage,
+            menu: Self::build_menu(ctx, &entrants, elimination),
+            entrants,
+            elimination,
+            first_round: 0,
+            name_input: TextInput::new(ctx, MAX_NAME_LENGTH),
+            is_done: false,
+            is_started: false,
+        };
+        scene.set_stage(ctx, world, stage);
+        scene
+    }
+
+    fn title(font: graphics::Font, title: &str) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(title)
+                .color(graphics::Color::from((192, 128, 64, 255)))
+                .font(font)
+                .scale(graphics::Scale::uniform(48.0)),
+        )
+    }
+
+    fn text(&self, text: &str, color: graphics::Color) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(text)
+                .color(color)
+                .font(self.font)
+                .scale(graphics::Scale::uniform(18.0)),
+        )
+    }
+
+    fn build_menu(
+        ctx: &mut Context,
+        entrants: &[String],
+        elimination: Elimination,
+    ) -> Menu<TournamentAction> {
+        let format_names: Vec<&str> = Elimination::ALL.iter().map(|e| e.name()).collect();
+        let format_index = Elimination::ALL
+            .iter()
+            .position(|e| *e == elimination)
+            .unwrap_or(0);
+
+        let add = MenuItem::action(TournamentAction::Add, "Add player");
+        let remove = MenuItem::action(TournamentAction::RemoveLast, "Remove last");
+        let start = MenuItem::action(TournamentAction::Start, "Start");
+
+        let items = vec![
+            if entrants.len() < MAX_ENTRANTS {
+                add
+            } else {
+                add.disabled()
+            },
+            if entrants.is_empty() {
+                remove.disabled()
+            } else {
+                remove
+            },
+            MenuItem::choice(
+                TournamentAction::Format,
+                "Format",
+                &format_names,
+                format_index,
+            ),
+            if entrants.len() >= MIN_ENTRANTS {
+                start
+            } else {
+                start.disabled()
+            },
+        ];
+
+        Menu::new(ctx, items)
+    }
+
+    fn set_stage(&mut self, ctx: &mut Context, world: &World, stage: Stage) {
+        self.stage = stage;
+        let title = match (stage, &world.tournament) {
+            (Stage::Bracket, Some(tournament)) => match tournament.champion() {
+                Some(champion) => format!("{} wins!", tournament.entrants[champion]),
+                None => format!("Round {}", tournament.round),
+            },
+            (Stage::NameEntry, _) => String::from("Player name"),
+            _ => String::from("Tournament"),
+        };
+        self.title_text = Self::title(self.font, &title);
+
+        match stage {
+            Stage::Entrants => {
+                self.menu = Self::build_menu(ctx, &self.entrants, self.elimination);
+            }
+            Stage::NameEntry => self.name_input = TextInput::new(ctx, MAX_NAME_LENGTH),
+            Stage::Bracket => {
+                if let Some(tournament) = &world.tournament {
+                    self.first_round = tournament.round.saturating_sub(VISIBLE_ROUNDS);
+                }
+            }
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        event: Option<MenuEvent<TournamentAction>>,
+    ) {
+        let feedback_sound = event.as_ref().map(|event| event.feedback_sound());
+
+        match event {
+            Some(MenuEvent::Activated(TournamentAction::Add)) => {
+                self.set_stage(ctx, world, Stage::NameEntry)
+            }
+            Some(MenuEvent::Activated(TournamentAction::RemoveLast)) => {
+                self.entrants.pop();
+                self.set_stage(ctx, world, Stage::Entrants);
+            }
+            Some(MenuEvent::Changed(TournamentAction::Format)) => {
+                if let Some(index) = self.menu.choice_index(TournamentAction::Format) {
+                    self.elimination = Elimination::ALL[index];
+                }
+            }
+            Some(MenuEvent::Activated(TournamentAction::Start)) => {
+                // Seeded by ladder rating, entry order breaks ties
+                let mut seeded = self.entrants.clone();
+                seeded.sort_by(|a, b| world.ladder.rating(b).total_cmp(&world.ladder.rating(a)));
+                let tournament = Tournament::new(seeded, self.elimination);
+                tournament.save(ctx);
+                world.tournament = Some(tournament);
+                self.set_stage(ctx, world, Stage::Bracket);
+            }
+            Some(MenuEvent::Back) => self.is_done = true,
+            _ => {}
+        };
+
+        if let Some(sound) = feedback_sound {
+            world.play_sound(ctx, sound);
+        }
+    }
+
+    fn handle_name_entry(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        event: Option<TextInputEvent>,
+    ) {
+        match event {
+            Some(TextInputEvent::Submitted(name)) => {
+                // Entrants are profiles, so the ladder picks up tournament matches too
+                let index = world.profiles.find_or_create(&name);
+                world.profiles.save(ctx);
+                let name = world.profiles.profiles[index].name.clone();
+                if !self.entrants.contains(&name) {
+                    self.entrants.push(name);
+                }
+                self.set_stage(ctx, world, Stage::Entrants);
+            }
+            Some(TextInputEvent::Cancelled) => self.set_stage(ctx, world, Stage::Entrants),
+            None => {}
+        }
+    }
+
+    fn bracket_input(&mut self, world: &mut World, keycode: KeyCode) {
+        let tournament = match &world.tournament {
+            Some(tournament) => tournament,
+            None => return,
+        };
+
+        match keycode {
+            KeyCode::Return | KeyCode::Space => {
+                if tournament.champion().is_some() {
+                    self.is_done = true;
+                } else {
+                    self.is_started = true;
+                }
+            }
+            KeyCode::Back => self.is_done = true,
+            KeyCode::Left | KeyCode::A => self.first_round = self.first_round.saturating_sub(1),
+            KeyCode::Right | KeyCode::D if self.first_round + VISIBLE_ROUNDS < tournament.round => {
+                self.first_round += 1
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_entrants(&mut self, ctx: &mut Context) {
+        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
+
+        // Two columns of eight names
+        for (index, name) in self.entrants.iter().enumerate() {
+            let column = (index / 8) as f32;
+            let row = (index % 8) as f32;
+            let dest = na::Point2::new(
+                ARENA_WIDTH * 0.2 + column * ARENA_WIDTH * 0.35,
+                LIST_TOP + row * ROW_HEIGHT,
+            );
+            let entrant_text = self.text(&format!("{}. {}", index + 1, name), silver);
+            graphics::draw(ctx, &entrant_text, DrawParam::new().dest(dest)).unwrap();
+        }
+
+        let menu_top = LIST_TOP + ROW_HEIGHT * 9.;
+        if self.stage == Stage::NameEntry {
+            self.name_input
+                .draw(ctx, na::Point2::new(ARENA_WIDTH * 0.25, menu_top));
+        } else {
+            self.menu
+                .draw(ctx, na::Point2::new(ARENA_WIDTH * 0.25, menu_top));
+        }
+    }
+
+    fn draw_match(
+        &self,
+        ctx: &mut Context,
+        tournament: &Tournament,
+        played: &TournamentMatch,
+        is_next: bool,
+        position: na::Point2<f32>,
+    ) {
+        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
+        let highlight = graphics::Color::from((192, 128, 64, 255));
+        let dim = graphics::Color::from_rgba(110, 110, 110, 255);
+
+        if tournament.elimination == Elimination::Double {
+            let tag = match played.bracket {
+                Bracket::Winners => "W",
+                Bracket::Losers => "L",
+                Bracket::Final => "F",
+            };
+            let tag_text = self.text(tag, dim);
+            graphics::draw(ctx, &tag_text, DrawParam::new().dest(position)).unwrap();
+        }
+
+        let lines = [(Some(played.home), 0usize), (played.away, 1)];
+        for (entrant, side) in lines.iter() {
+            let y = position.y + *side as f32 * (ROW_HEIGHT - 4.);
+            let (name, color) = match entrant {
+                Some(entrant) if played.winner == Some(*entrant) => {
+                    (tournament.entrants[*entrant].as_str(), highlight)
+                }
+                Some(entrant) if is_next => (tournament.entrants[*entrant].as_str(), silver),
+                Some(entrant) if played.winner.is_some() => {
+                    (tournament.entrants[*entrant].as_str(), dim)
+                }
+                Some(entrant) => (tournament.entrants[*entrant].as_str(), silver),
+                None => ("(bye)", dim),
+            };
+            let prefix = if is_next { "> " } else { "" };
+            let name_text = self.text(&format!("{}{}", prefix, name), color);
+            graphics::draw(
+                ctx,
+                &name_text,
+                DrawParam::new().dest(na::Point2::new(position.x + 20., y)),
+            )
+            .unwrap();
+
+            if let Some(score) = played.score {
+                let score_text = self.text(&score[*side].to_string(), color);
+                graphics::draw(
+                    ctx,
+                    &score_text,
+                    DrawParam::new().dest(na::Point2::new(position.x + COLUMN_WIDTH - 40., y)),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn draw_bracket(&self, ctx: &mut Context, tournament: &Tournament) {
+        let silver = graphics::Color::from_rgba(192, 192, 192, 255);
+        let highlight = graphics::Color::from((192, 128, 64, 255));
+        let next_match = tournament.next_match();
+
+        let last_round = (self.first_round + VISIBLE_ROUNDS).min(tournament.round);
+        for (column, round) in (self.first_round + 1..=last_round).enumerate() {
+            let x = 20. + column as f32 * COLUMN_WIDTH;
+            let round_matches: Vec<(usize, &TournamentMatch)> = tournament
+                .matches
+                .iter()
+                .enumerate()
+                .filter(|(_, played)| played.round == round)
+                .collect();
+
+            let is_final = round_matches
+                .iter()
+                .all(|(_, played)| played.bracket == Bracket::Final);
+            let header = if is_final {
+                String::from("Final")
+            } else {
+                format!("Round {}", round)
+            };
+            let header_text = self.text(&header, highlight);
+            graphics::draw(
+                ctx,
+                &header_text,
+                DrawParam::new().dest(na::Point2::new(x + 20., LIST_TOP - ROW_HEIGHT * 1.5)),
+            )
+            .unwrap();
+
+            for (row, (index, played)) in round_matches.iter().enumerate() {
+                let position = na::Point2::new(x, LIST_TOP + row as f32 * MATCH_HEIGHT);
+                self.draw_match(
+                    ctx,
+                    tournament,
+                    played,
+                    next_match == Some(*index),
+                    position,
+                );
+            }
+        }
+
+        let footer = match (tournament.champion(), next_match) {
+            (Some(_), _) => String::from("Return: finish the tournament"),
+            (None, Some(index)) => {
+                let next = &tournament.matches[index];
+                format!(
+                    "Return: play {} vs {}    Backspace: leave",
+                    tournament.entrants[next.home],
+                    next.away
+                        .map(|away| tournament.entrants[away].as_str())
+                        .unwrap_or("")
+                )
+            }
+            (None, None) => String::new(),
+        };
+        let footer_text = self.text(&footer, silver);
+        let footer_x = (ARENA_WIDTH - footer_text.width(ctx) as f32) * 0.5;
+        graphics::draw(
+            ctx,
+            &footer_text,
+            DrawParam::new().dest(na::Point2::new(footer_x, ARENA_HEIGHT - 50.)),
+        )
+        .unwrap();
+    }
+}
+
+impl Scene<World> for TournamentScene {
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> Option<Box<dyn Scene<World>>> {
+        if self.is_done {
+            // An unfinished tournament stays on disk and is resumed from the menu
+            world.tournament = None;
+            return Some(Box::new(ms::new(ctx, world)));
+        }
+
+        if !self.is_started {
+            return None;
+        }
+
+        let (home, away) = match &world.tournament {
+            Some(tournament) => match tournament.next_match() {
+                Some(index) => {
+                    let next = &tournament.matches[index];
+                    (
+                        tournament.entrants[next.home].clone(),
+                        next.away
+                            .map(|away| tournament.entrants[away].clone())
+                            .unwrap_or_default(),
+                    )
+                }
+                None => return None,
+            },
+            None => return None,
+        };
+
+        let home = world.profiles.find_or_create(&home);
+        let away = world.profiles.find_or_create(&away);
+        world.profiles.save(ctx);
+
+        world.set_mode(GameMode::Versus);
+        world.set_lineup(&[Seat::Profile(home), Seat::Profile(away)]);
+        world.arena = Arena::classic();
+        world.reset(ctx);
+        Some(Box::new(gs::new(ctx)))
+    }
+
+    fn draw(&mut self, ctx: &mut Context, world: &mut World) {
+        let title_text_width = self.title_text.width(ctx) as f32;
+        let title_dest = na::Point2::new((ARENA_WIDTH - title_text_width) * 0.5, 20.);
+        graphics::draw(ctx, &self.title_text, DrawParam::new().dest(title_dest)).unwrap();
+
+        match (&world.tournament, self.stage) {
+            (Some(tournament), Stage::Bracket) => self.draw_bracket(ctx, tournament),
+            _ => self.draw_entrants(ctx),
+        }
+    }
+
+    fn input(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        keycode: KeyCode,
+        _pressed: bool,
+        _repeat: bool,
+    ) {
+        match self.stage {
+            Stage::Entrants => {
+                let event = self.menu.input(keycode);
+                self.handle_event(ctx, world, event);
+            }
+            Stage::NameEntry => {
+                let event = self.name_input.input(keycode);
+                self.handle_name_entry(ctx, world, event);
+            }
+            Stage::Bracket => self.bracket_input(world, keycode),
+        }
+    }
+
+    fn text_input(&mut self, _ctx: &mut Context, _world: &mut World, character: char) {
+        if self.stage == Stage::NameEntry {
+            self.name_input.text_input(character);
+        }
+    }
+
+    fn mouse_motion(&mut self, ctx: &mut Context, world: &mut World, x: f32, y: f32) {
+        if self.stage == Stage::Entrants {
+            let event = self.menu.mouse_motion(x, y);
+            self.handle_event(ctx, world, event);
+        }
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        world: &mut World,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) {
+        match (self.stage, button) {
+            (Stage::Entrants, MouseButton::Left) => {
+                let event = self.menu.mouse_click(x, y);
+                self.handle_event(ctx, world, event);
+            }
+            (Stage::Entrants, MouseButton::Right) => {
+                let event = self.menu.input(KeyCode::Back);
+                self.handle_event(ctx, world, event);
+            }
+            (Stage::Bracket, MouseButton::Left) => self.bracket_input(world, KeyCode::Return),
+            (Stage::Bracket, MouseButton::Right) => self.bracket_input(world, KeyCode::Back),
+            _ => {}
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Tournament Scene"
+    }
+}
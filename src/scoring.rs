@@ -0,0 +1,341 @@
+// Decides when a single game is won
+pub trait ScoringRule {
+    // Index of the game winner given everyone's points, if decided
+    fn game_winner(&self, points: &[i32]) -> Option<usize>;
+    // Short status for the HUD, e.g. "Deuce"
+    fn status(&self, _points: &[i32]) -> Option<String> {
+        None
+    }
+}
+
+// The leader and the best score of everyone else
+fn leader(points: &[i32]) -> (usize, i32, i32) {
+    let mut leader = 0;
+    for (index, score) in points.iter().enumerate() {
+        if *score > points[leader] {
+            leader = index;
+        }
+    }
+    let runner_up = points
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != leader)
+        .map(|(_, score)| *score)
+        .max()
+        .unwrap_or(0);
+    (leader, points[leader], runner_up)
+}
+
+// The first to reach the target wins
+pub struct FirstTo {
+    pub target: i32,
+}
+
+impl ScoringRule for FirstTo {
+    fn game_winner(&self, points: &[i32]) -> Option<usize> {
+        points.iter().position(|score| *score >= self.target)
+    }
+}
+
+// Reach the target with a two point lead. With golden point there's no
+// advantage, once both are one point short of the target the next point wins.
+pub struct WinByTwo {
+    pub target: i32,
+    pub golden_point: bool,
+}
+
+impl WinByTwo {
+    fn is_deuce(&self, points: &[i32]) -> bool {
+        let (_, best, runner_up) = leader(points);
+        runner_up >= self.target - 1 && best >= self.target - 1
+    }
+}
+
+impl ScoringRule for WinByTwo {
+    fn game_winner(&self, points: &[i32]) -> Option<usize> {
+        let (leader, best, runner_up) = leader(points);
+        let golden_point_won =
+            self.golden_point && runner_up >= self.target - 1 && best > runner_up;
+        if (best >= self.target && best - runner_up >= 2) || golden_point_won {
+            Some(leader)
+        } else {
+            None
+        }
+    }
+
+    fn status(&self, points: &[i32]) -> Option<String> {
+        if !self.is_deuce(points) {
+            return None;
+        }
+
+        let (_, best, runner_up) = leader(points);
+        if self.golden_point {
+            Some(String::from("Golden point"))
+        } else if best != runner_up {
+            Some(String::from("Advantage"))
+        } else {
+            Some(String::from("Deuce"))
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ScoringKind {
+    FirstTo,
+    WinByTwo,
+    GoldenPoint,
+}
+
+impl ScoringKind {
+    pub const ALL: [ScoringKind; 3] = [
+        ScoringKind::FirstTo,
+        ScoringKind::WinByTwo,
+        ScoringKind::GoldenPoint,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoringKind::FirstTo => "First to N",
+            ScoringKind::WinByTwo => "Win by two",
+            ScoringKind::GoldenPoint => "Golden point",
+        }
+    }
+
+    pub fn rule(self, target: i32) -> Box<dyn ScoringRule> {
+        match self {
+            ScoringKind::FirstTo => Box::new(FirstTo { target }),
+            ScoringKind::WinByTwo => Box::new(WinByTwo {
+                target,
+                golden_point: false,
+            }),
+            ScoringKind::GoldenPoint => Box::new(WinByTwo {
+                target,
+                golden_point: true,
+            }),
+        }
+    }
+}
+
+pub enum PointOutcome {
+    Continue,
+    SetWon(usize),
+    MatchWon(usize),
+}
+
+// Points and sets of the match in progress, a single game is a best of one
+pub struct Scoring {
+    rule: Box<dyn ScoringRule>,
+    best_of: i32,
+    points: Vec<i32>,
+    sets_won: Vec<i32>,
+    // Final points of every finished set
+    sets: Vec<Vec<i32>>,
+}
+
+impl Scoring {
+    pub fn new(rule: Box<dyn ScoringRule>, best_of: i32, players: usize) -> Self {
+        Self {
+            rule,
+            best_of: best_of.max(1),
+            points: vec![0; players],
+            sets_won: vec![0; players],
+            sets: Vec::new(),
+        }
+    }
+
+    pub fn points(&self, player: usize) -> i32 {
+        self.points[player]
+    }
+
+    // Points scored by everyone in the current set
+    pub fn points_played(&self) -> i32 {
+        self.points.iter().sum()
+    }
+
+    pub fn is_multi_set(&self) -> bool {
+        self.best_of > 1
+    }
+
+    pub fn sets_won(&self, player: usize) -> i32 {
+        self.sets_won[player]
+    }
+
+    pub fn sets(&self) -> &[Vec<i32>] {
+        &self.sets
+    }
+
+    // Sets won in a multi set match, otherwise the points
+    pub fn match_score(&self) -> Vec<i32> {
+        if self.is_multi_set() {
+            self.sets_won.clone()
+        } else {
+            self.points.clone()
+        }
+    }
+
+    pub fn status(&self) -> Option<String> {
+        self.rule.status(&self.points)
+    }
+
+    pub fn point_scored(&mut self, scorer: usize) -> PointOutcome {
+        self.points[scorer] += 1;
+
+        let winner = match self.rule.game_winner(&self.points) {
+            Some(winner) => winner,
+            None => return PointOutcome::Continue,
+        };
+
+        self.sets_won[winner] += 1;
+        self.sets.push(self.points.clone());
+
+        if self.sets_won[winner] > self.best_of / 2 {
+            PointOutcome::MatchWon(winner)
+        } else {
+            self.points.iter_mut().for_each(|points| *points = 0);
+            PointOutcome::SetWon(winner)
+        }
+    }
+}
+
+// Survival play: the match ends once `survivor` has lost all lives
+pub struct LastLife {
+    pub survivor: usize,
+    pub lives: i32,
+}
+
+impl ScoringRule for LastLife {
+    fn game_winner(&self, points: &[i32]) -> Option<usize> {
+        points
+            .iter()
+            .enumerate()
+            .position(|(index, score)| index != self.survivor && *score >= self.lives)
+    }
+}
+
+// Free-for-all: every point is a goal let in by that player, who is out once
+// they've let in `lives` of them. The last one still in wins.
+pub struct LastStanding {
+    pub lives: i32,
+}
+
+impl ScoringRule for LastStanding {
+    fn game_winner(&self, points: &[i32]) -> Option<usize> {
+        let mut standing = points
+            .iter()
+            .enumerate()
+            .filter(|(_, conceded)| **conceded < self.lives);
+        match (standing.next(), standing.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    }
+}
+
+// Timed play: points never end the game, the clock does
+pub struct NoLimit;
+
+impl ScoringRule for NoLimit {
+    fn game_winner(&self, _points: &[i32]) -> Option<usize> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Play the points in order, each entry is who scored, and return the last outcome
+    fn play(scoring: &mut Scoring, scorers: &[usize]) -> PointOutcome {
+        let mut outcome = PointOutcome::Continue;
+        for scorer in scorers {
+            outcome = scoring.point_scored(*scorer);
+        }
+        outcome
+    }
+
+    fn win_by_two(golden_point: bool) -> WinByTwo {
+        WinByTwo {
+            target: 5,
+            golden_point,
+        }
+    }
+
+    #[test]
+    fn first_to_target_wins() {
+        let rule = FirstTo { target: 3 };
+        assert_eq!(rule.game_winner(&[2, 2]), None);
+        assert_eq!(rule.game_winner(&[2, 3]), Some(1));
+    }
+
+    #[test]
+    fn deuce_needs_a_two_point_lead() {
+        let rule = win_by_two(false);
+        assert_eq!(rule.game_winner(&[4, 3]), None);
+        assert_eq!(rule.status(&[4, 3]), None);
+        assert_eq!(rule.status(&[4, 4]).as_deref(), Some("Deuce"));
+        assert_eq!(rule.game_winner(&[5, 4]), None);
+        assert_eq!(rule.status(&[5, 4]).as_deref(), Some("Advantage"));
+        assert_eq!(rule.status(&[5, 5]).as_deref(), Some("Deuce"));
+        assert_eq!(rule.game_winner(&[7, 5]), Some(0));
+        assert_eq!(rule.game_winner(&[5, 2]), Some(0));
+    }
+
+    #[test]
+    fn golden_point_wins_straight_from_deuce() {
+        let rule = win_by_two(true);
+        assert_eq!(rule.game_winner(&[4, 3]), None);
+        assert_eq!(rule.status(&[4, 4]).as_deref(), Some("Golden point"));
+        assert_eq!(rule.game_winner(&[4, 5]), Some(1));
+        assert_eq!(rule.game_winner(&[5, 3]), Some(0));
+    }
+
+    #[test]
+    fn last_life_and_last_standing() {
+        let rule = LastLife {
+            survivor: 0,
+            lives: 3,
+        };
+        assert_eq!(rule.game_winner(&[10, 2]), None);
+        assert_eq!(rule.game_winner(&[10, 3]), Some(1));
+
+        let rule = LastStanding { lives: 2 };
+        assert_eq!(rule.game_winner(&[2, 1, 0, 2]), None);
+        assert_eq!(rule.game_winner(&[2, 1, 2, 2]), Some(1));
+        assert_eq!(NoLimit.game_winner(&[100, 0]), None);
+    }
+
+    #[test]
+    fn sets_roll_over_until_the_match_is_won() {
+        let mut scoring = Scoring::new(Box::new(FirstTo { target: 2 }), 3, 2);
+        assert!(scoring.is_multi_set());
+
+        assert!(matches!(play(&mut scoring, &[0]), PointOutcome::Continue));
+        assert!(matches!(play(&mut scoring, &[0]), PointOutcome::SetWon(0)));
+        assert_eq!(scoring.points(0), 0);
+        assert_eq!(scoring.match_score(), vec![1, 0]);
+
+        assert!(matches!(
+            play(&mut scoring, &[1, 0, 1]),
+            PointOutcome::SetWon(1)
+        ));
+        assert!(matches!(
+            play(&mut scoring, &[1, 1]),
+            PointOutcome::MatchWon(1)
+        ));
+        assert_eq!(scoring.match_score(), vec![1, 2]);
+        assert_eq!(scoring.sets(), &[vec![2, 0], vec![1, 2], vec![0, 2]]);
+    }
+
+    #[test]
+    fn single_game_ends_the_match() {
+        let mut scoring = Scoring::new(Box::new(win_by_two(true)), 1, 2);
+        assert!(!scoring.is_multi_set());
+        let outcome = play(&mut scoring, &[0, 1, 0, 1, 0, 1, 0, 1]);
+        assert!(matches!(outcome, PointOutcome::Continue));
+        assert!(matches!(
+            play(&mut scoring, &[1]),
+            PointOutcome::MatchWon(1)
+        ));
+        assert_eq!(scoring.match_score(), vec![4, 5]);
+    }
+}
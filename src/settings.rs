@@ -0,0 +1,99 @@
+use crate::{
+    conf, graphics,
+    mode::{BallLaunch, Difficulty},
+    particles::ParticleColor,
+    powerups::PowerUpKind,
+    scoring::ScoringKind,
+    Context, GameResult,
+};
+
+// Options chosen from the options menu
+pub struct Settings {
+    pub fullscreen: bool,
+    // Sound effect volume from 0 to MAX_VOLUME
+    pub volume: i32,
+    pub muted: bool,
+    // Particles in a paddle hit burst, 0 turns particles off
+    pub particle_count: i32,
+    pub particle_color: ParticleColor,
+    // Seconds a particle lives
+    pub particle_lifetime: f32,
+    // Fewer particles and effects for low-end machines
+    pub reduced_effects: bool,
+    pub screen_shake: bool,
+    pub hit_stop: bool,
+    pub ball_trail: bool,
+    pub squash_stretch: bool,
+    // Computer opponent strength in the solo modes
+    pub difficulty: Difficulty,
+    pub scoring: ScoringKind,
+    // Number of sets in a match, 1 plays a single game
+    pub best_of: i32,
+    // Length of a timed match in minutes
+    pub time_limit: i32,
+    // Speed the ball up when a timed match goes to sudden death
+    pub sudden_death_speedup: bool,
+    pub ball_launch: BallLaunch,
+    // Items that change the game when the ball picks them up
+    pub power_ups: bool,
+    // How likely each item is to spawn, indexed like `PowerUpKind::ALL`
+    pub power_up_weights: [i32; PowerUpKind::COUNT],
+}
+
+pub const MAX_PARTICLE_COUNT: i32 = 40;
+
+pub const MAX_VOLUME: i32 = 10;
+
+pub const MAX_POWER_UP_WEIGHT: i32 = 5;
+
+impl Settings {
+    pub fn new() -> Self {
+        Self {
+            fullscreen: false,
+            volume: 7,
+            muted: false,
+            particle_count: 12,
+            particle_color: ParticleColor::White,
+            particle_lifetime: 0.6,
+            reduced_effects: false,
+            screen_shake: false,
+            hit_stop: false,
+            ball_trail: false,
+            squash_stretch: false,
+            difficulty: Difficulty::Normal,
+            scoring: ScoringKind::FirstTo,
+            best_of: 1,
+            time_limit: 3,
+            sudden_death_speedup: true,
+            ball_launch: BallLaunch::Every(10),
+            power_ups: false,
+            power_up_weights: PowerUpKind::ALL.map(PowerUpKind::default_weight),
+        }
+    }
+
+    // The "classic" preset turns every game feel effect off, "juicy" turns them all on
+    pub fn set_juice(&mut self, enabled: bool) {
+        self.screen_shake = enabled;
+        self.hit_stop = enabled;
+        self.ball_trail = enabled;
+        self.squash_stretch = enabled;
+    }
+
+    pub fn effective_volume(&self) -> f32 {
+        if self.muted {
+            0.
+        } else {
+            self.volume as f32 / MAX_VOLUME as f32
+        }
+    }
+
+    pub fn set_fullscreen(&mut self, ctx: &mut Context, fullscreen: bool) -> GameResult {
+        self.fullscreen = fullscreen;
+        let fullscreen_type = if fullscreen {
+            conf::FullscreenType::Desktop
+        } else {
+            conf::FullscreenType::Windowed
+        };
+        graphics::set_fullscreen(ctx, fullscreen_type)
+    }
+}
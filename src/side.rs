@@ -0,0 +1,75 @@
+use crate::na;
+
+// Edge of the arena a player defends
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Left, Side::Right, Side::Top, Side::Bottom];
+
+    // Paddles on the top and bottom edges move left and right
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Side::Top | Side::Bottom)
+    }
+
+    // Unit vector pointing from the edge into the arena
+    pub fn normal(self) -> na::Vector2<f32> {
+        match self {
+            Side::Left => na::Vector2::new(1., 0.),
+            Side::Right => na::Vector2::new(-1., 0.),
+            Side::Top => na::Vector2::new(0., 1.),
+            Side::Bottom => na::Vector2::new(0., -1.),
+        }
+    }
+
+    // Component of `v` along the edge
+    pub fn along(self, v: na::Vector2<f32>) -> f32 {
+        if self.is_horizontal() {
+            v.x
+        } else {
+            v.y
+        }
+    }
+
+    // Component of `v` across the edge, towards the opposite side
+    pub fn depth(self, v: na::Vector2<f32>) -> f32 {
+        if self.is_horizontal() {
+            v.y
+        } else {
+            v.x
+        }
+    }
+
+    // Where the edge of an arena of the given size lies along the depth axis
+    pub fn edge(self, width: f32, height: f32) -> f32 {
+        match self {
+            Side::Left | Side::Top => 0.,
+            Side::Right => width,
+            Side::Bottom => height,
+        }
+    }
+
+    // Length of the edge of an arena of the given size
+    pub fn length(self, width: f32, height: f32) -> f32 {
+        if self.is_horizontal() {
+            width
+        } else {
+            height
+        }
+    }
+
+    // True if `position` has gone past this edge and out of the arena
+    pub fn is_past(self, position: na::Point2<f32>, width: f32, height: f32) -> bool {
+        let depth = self.depth(position.coords);
+        let edge = self.edge(width, height);
+        match self {
+            Side::Left | Side::Top => depth < edge,
+            Side::Right | Side::Bottom => depth > edge,
+        }
+    }
+}
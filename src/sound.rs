@@ -0,0 +1,89 @@
+use crate::{
+    audio::{self, SoundSource},
+    events::{EventListener, GameEvent},
+    settings::Settings,
+    synth::{self, Tone, SAMPLE_RATE},
+    Context,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Sound {
+    PaddleHit,
+    WallHit,
+    Score,
+    MenuMove,
+    MenuConfirm,
+}
+
+impl Sound {
+    const ALL: [Sound; 5] = [
+        Sound::PaddleHit,
+        Sound::WallHit,
+        Sound::Score,
+        Sound::MenuMove,
+        Sound::MenuConfirm,
+    ];
+
+    pub fn tones(self) -> Vec<Tone> {
+        match self {
+            Sound::PaddleHit => vec![Tone::new(459., 0.05)],
+            Sound::WallHit => vec![Tone::new(226., 0.03)],
+            Sound::Score => vec![Tone::new(490., 0.12), Tone::new(245., 0.2)],
+            Sound::MenuMove => vec![Tone::new(660., 0.03)],
+            Sound::MenuConfirm => vec![Tone::new(880., 0.05), Tone::new(1320., 0.08)],
+        }
+    }
+}
+
+// Holds the rendered sound effects, ready to be played
+pub struct SoundPlayer {
+    sounds: Vec<(Sound, audio::SoundData)>,
+}
+
+impl SoundPlayer {
+    pub fn new() -> Self {
+        let sounds = Sound::ALL
+            .iter()
+            .map(|sound| {
+                let samples = synth::render_sequence(&sound.tones(), SAMPLE_RATE);
+                let wav = synth::encode_wav(&samples, SAMPLE_RATE);
+                (*sound, audio::SoundData::from(wav))
+            })
+            .collect();
+
+        Self { sounds }
+    }
+
+    // `volume` is in the 0.0..=1.0 range
+    pub fn play(&self, ctx: &mut Context, sound: Sound, volume: f32) {
+        if volume <= 0. {
+            return;
+        }
+
+        let data = match self.sounds.iter().find(|(s, _)| *s == sound) {
+            Some((_, data)) => data.clone(),
+            None => return,
+        };
+
+        // A missing or busy audio device shouldn't stop the game
+        if let Ok(mut source) = audio::Source::from_data(ctx, data) {
+            source.set_volume(volume);
+            let _ = source.play_detached();
+        }
+    }
+}
+
+impl EventListener for SoundPlayer {
+    fn on_event(&mut self, ctx: &mut Context, settings: &Settings, event: &GameEvent) {
+        let sound = match event {
+            GameEvent::BallHitPaddle { .. } => Sound::PaddleHit,
+            GameEvent::BallHitWall { .. }
+            | GameEvent::BallHitBall { .. }
+            | GameEvent::BrickBroken { .. } => Sound::WallHit,
+            GameEvent::PointScored { .. } => Sound::Score,
+            GameEvent::PowerUpCollected { .. } => Sound::MenuConfirm,
+            _ => return,
+        };
+        self.play(ctx, sound, settings.effective_volume());
+    }
+}
@@ -0,0 +1,180 @@
+use serde::Serialize;
+
+use crate::{
+    events::{EventListener, GameEvent},
+    settings::Settings,
+    storage::Storage,
+    Context, GameResult,
+};
+
+// Statistics collected over a single match, player 1 has index 0
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct MatchStats {
+    pub players: Vec<String>,
+    pub hits: Vec<u32>,
+    // Paddle hits in every finished rally
+    pub rallies: Vec<u32>,
+    pub max_speed: f32,
+    pub points_won_on_serve: Vec<u32>,
+    // Who scored each point, in order, goals nobody is credited with are left out
+    pub points: Vec<usize>,
+    // Breakout bricks each player broke and the bonus points they were worth
+    pub bricks: Vec<u32>,
+    pub brick_points: Vec<u32>,
+
+    #[serde(skip)]
+    rally_hits: u32,
+    #[serde(skip)]
+    speed_total: f32,
+    #[serde(skip)]
+    speed_samples: u32,
+    #[serde(skip)]
+    server: usize,
+}
+
+impl MatchStats {
+    pub fn new(players: Vec<String>) -> Self {
+        let count = players.len();
+        Self {
+            players,
+            hits: vec![0; count],
+            bricks: vec![0; count],
+            brick_points: vec![0; count],
+            points_won_on_serve: vec![0; count],
+            ..Self::default()
+        }
+    }
+
+    pub fn score(&self) -> Vec<usize> {
+        (0..self.players.len())
+            .map(|player| {
+                self.points
+                    .iter()
+                    .filter(|&&scorer| scorer == player)
+                    .count()
+            })
+            .collect()
+    }
+
+    pub fn total_rally_hits(&self) -> u32 {
+        self.rallies.iter().sum()
+    }
+
+    pub fn longest_rally(&self) -> u32 {
+        self.rallies.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn average_speed(&self) -> f32 {
+        if self.speed_samples == 0 {
+            0.
+        } else {
+            self.speed_total / self.speed_samples as f32
+        }
+    }
+
+    // Score difference after every point, positive when player 1 leads
+    pub fn momentum(&self) -> Vec<i32> {
+        self.points
+            .iter()
+            .scan(0, |lead, &scorer| {
+                *lead += if scorer == 0 { 1 } else { -1 };
+                Some(*lead)
+            })
+            .collect()
+    }
+
+    // Write the stats as JSON to the user config directory
+    pub fn export(&self, ctx: &mut Context, path: &str) -> GameResult {
+        Storage::save_json(ctx, path, &ExportedStats::from(self))
+    }
+}
+
+// Flattened form of the stats with the derived values filled in
+#[derive(Serialize)]
+struct ExportedStats<'a> {
+    #[serde(flatten)]
+    stats: &'a MatchStats,
+    score: Vec<usize>,
+    total_rally_hits: u32,
+    longest_rally: u32,
+    average_speed: f32,
+}
+
+impl<'a> From<&'a MatchStats> for ExportedStats<'a> {
+    fn from(stats: &'a MatchStats) -> Self {
+        Self {
+            stats,
+            score: stats.score(),
+            total_rally_hits: stats.total_rally_hits(),
+            longest_rally: stats.longest_rally(),
+            average_speed: stats.average_speed(),
+        }
+    }
+}
+
+impl EventListener for MatchStats {
+    fn on_event(&mut self, _ctx: &mut Context, _settings: &Settings, event: &GameEvent) {
+        match event {
+            GameEvent::ServeStarted { server } => {
+                self.server = *server;
+                self.rally_hits = 0;
+            }
+            GameEvent::BallHitPaddle { player, speed, .. } => {
+                self.hits[*player] += 1;
+                self.rally_hits += 1;
+                self.speed_total += speed;
+                self.speed_samples += 1;
+                self.max_speed = self.max_speed.max(*speed);
+            }
+            GameEvent::BrickBroken {
+                player: Some(player),
+                points,
+                ..
+            } => {
+                self.bricks[*player] += 1;
+                self.brick_points[*player] += *points as u32;
+            }
+            GameEvent::PointScored { scorer, .. } => {
+                // With several balls in play each one that exits ends its own rally
+                self.rallies.push(self.rally_hits);
+                self.rally_hits = 0;
+                if let Some(scorer) = *scorer {
+                    self.points.push(scorer);
+                    if scorer == self.server {
+                        self.points_won_on_serve[scorer] += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(points: &[usize]) -> MatchStats {
+        let mut stats = MatchStats::new(vec![String::from("Left"), String::from("Right")]);
+        stats.points = points.to_vec();
+        stats
+    }
+
+    #[test]
+    fn momentum_follows_the_lead() {
+        assert_eq!(
+            stats(&[0, 0, 1, 1, 1, 0]).momentum(),
+            vec![1, 2, 1, 0, -1, 0]
+        );
+    }
+
+    #[test]
+    fn momentum_is_empty_without_points() {
+        assert!(stats(&[]).momentum().is_empty());
+    }
+
+    #[test]
+    fn momentum_counts_other_players_against_player_1() {
+        assert_eq!(stats(&[2, 3, 0]).momentum(), vec![-1, -2, -1]);
+    }
+}
@@ -0,0 +1,76 @@
+use std::io::{Read, Write};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{filesystem, Context, GameResult};
+
+pub struct Storage {}
+
+impl Storage {
+    // Read a TOML file from the user config directory, falling back to the
+    // default value if it is missing or can't be parsed. An unreadable file is
+    // copied to `<path>.bak` first so the next save doesn't lose it for good.
+    pub fn load<T: DeserializeOwned + Default>(ctx: &mut Context, path: &str) -> T {
+        let mut contents = String::new();
+        let read = filesystem::open(ctx, path)
+            .and_then(|mut file| Ok(file.read_to_string(&mut contents)?));
+
+        match read {
+            Ok(_) => toml::from_str(&contents).unwrap_or_else(|error| {
+                eprintln!("Could not parse {}: {}", path, error);
+                Self::back_up(ctx, path, &contents);
+                T::default()
+            }),
+            Err(_) => T::default(),
+        }
+    }
+
+    fn back_up(ctx: &mut Context, path: &str, contents: &str) {
+        let backup = format!("{}.bak", path);
+        let written = filesystem::create(ctx, &backup)
+            .and_then(|mut file| Ok(file.write_all(contents.as_bytes())?));
+        match written {
+            Ok(_) => eprintln!("Kept a copy of the unreadable file in {}", backup),
+            Err(error) => eprintln!("Could not back up {}: {}", path, error),
+        }
+    }
+
+    // Read a file shipped with the game or written by the player, JSON if the
+    // name ends in .json and TOML otherwise
+    pub fn read<T: DeserializeOwned>(ctx: &mut Context, path: &str) -> GameResult<T> {
+        let mut contents = String::new();
+        filesystem::open(ctx, path)?.read_to_string(&mut contents)?;
+
+        let parsed = if path.ends_with(".json") {
+            serde_json::from_str(&contents).map_err(|error| error.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|error| error.to_string())
+        };
+        parsed.map_err(ggez::GameError::ResourceLoadError)
+    }
+
+    pub fn save<T: Serialize>(ctx: &mut Context, path: &str, value: &T) -> GameResult {
+        let contents = toml::to_string_pretty(value)
+            .map_err(|error| ggez::GameError::ResourceLoadError(error.to_string()))?;
+        let mut file = filesystem::create(ctx, path)?;
+        file.write_all(contents.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn delete(ctx: &mut Context, path: &str) {
+        if filesystem::exists(ctx, path) {
+            if let Err(error) = filesystem::delete(ctx, path) {
+                eprintln!("Could not delete {}: {}", path, error);
+            }
+        }
+    }
+
+    // JSON is used for exports meant to be read by other tools
+    pub fn save_json<T: Serialize>(ctx: &mut Context, path: &str, value: &T) -> GameResult {
+        let contents = serde_json::to_string_pretty(value)
+            .map_err(|error| ggez::GameError::ResourceLoadError(error.to_string()))?;
+        let mut file = filesystem::create(ctx, path)?;
+        file.write_all(contents.as_bytes())?;
+        Ok(())
+    }
+}
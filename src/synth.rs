@@ -0,0 +1,139 @@
+// Procedural square wave synthesis, renders into plain sample buffers so no
+// audio device or asset files are needed
+
+pub const SAMPLE_RATE: u32 = 44_100;
+
+#[derive(Clone, Copy)]
+pub struct Tone {
+    pub frequency: f32,
+    // Length in seconds, including attack and release
+    pub duration: f32,
+    pub attack: f32,
+    pub release: f32,
+    pub amplitude: f32,
+}
+
+impl Tone {
+    pub fn new(frequency: f32, duration: f32) -> Self {
+        Self {
+            frequency,
+            duration,
+            attack: 0.002,
+            release: duration * 0.5,
+            amplitude: 0.3,
+        }
+    }
+
+    // Linear attack and release ramps, held at full level in between
+    pub fn envelope(&self, t: f32) -> f32 {
+        if t < 0. || t >= self.duration {
+            0.
+        } else if t < self.attack {
+            t / self.attack
+        } else if t > self.duration - self.release {
+            (self.duration - t) / self.release
+        } else {
+            1.
+        }
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        let phase = (t * self.frequency).fract();
+        let square = if phase < 0.5 { 1. } else { -1. };
+        square * self.amplitude * self.envelope(t)
+    }
+
+    // The last sample lands on the very end of the release, so the tone stops
+    // silent instead of with a click
+    pub fn render(&self, sample_rate: u32) -> Vec<f32> {
+        let sample_count = (self.duration * sample_rate as f32) as usize;
+        let last = sample_count.saturating_sub(1).max(1) as f32;
+        (0..sample_count)
+            .map(|index| self.sample(self.duration * (index as f32 / last)))
+            .collect()
+    }
+}
+
+// Render tones one after another into a single buffer
+pub fn render_sequence(tones: &[Tone], sample_rate: u32) -> Vec<f32> {
+    tones
+        .iter()
+        .flat_map(|tone| tone.render(sample_rate))
+        .collect()
+}
+
+// Encode samples in the -1.0..=1.0 range as a mono 16-bit PCM WAV file
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let channels: u16 = 1;
+    let bits_per_sample: u16 = 16;
+    let block_align = channels * bits_per_sample / 8;
+    let byte_rate = sample_rate * block_align as u32;
+    let data_size = (samples.len() * block_align as usize) as u32;
+
+    let mut bytes = Vec::with_capacity(44 + data_size as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&byte_rate.to_le_bytes());
+    bytes.extend_from_slice(&block_align.to_le_bytes());
+    bytes.extend_from_slice(&bits_per_sample.to_le_bytes());
+
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1., 1.) * i16::MAX as f32) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_length_matches_duration() {
+        let tone = Tone::new(440., 0.25);
+        assert_eq!(
+            tone.render(SAMPLE_RATE).len(),
+            (0.25 * SAMPLE_RATE as f32) as usize
+        );
+        assert_eq!(tone.render(8_000).len(), 2_000);
+    }
+
+    #[test]
+    fn envelope_starts_and_ends_silent() {
+        let tone = Tone::new(440., 0.1);
+        let samples = tone.render(SAMPLE_RATE);
+        assert_eq!(samples[0], 0.);
+        assert_eq!(samples[samples.len() - 1], 0.);
+        assert!(samples.iter().all(|sample| sample.abs() <= tone.amplitude));
+    }
+
+    #[test]
+    fn wav_header_sizes_match_payload() {
+        let samples = render_sequence(&[Tone::new(440., 0.05), Tone::new(880., 0.05)], 8_000);
+        let bytes = encode_wav(&samples, 8_000);
+        let size_at = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ]) as usize
+        };
+
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(size_at(4), bytes.len() - 8);
+        assert_eq!(size_at(40), bytes.len() - 44);
+        assert_eq!(size_at(40), samples.len() * 2);
+    }
+}
@@ -0,0 +1,464 @@
+use crate::{graphics, na, sound::Sound, Context, KeyCode, Paddle};
+
+const ITEM_SCALE: f32 = 36.0;
+const ITEM_SPACING: f32 = 50.;
+
+pub enum ItemKind<T> {
+    Action,
+    Slider {
+        value: i32,
+        min: i32,
+        max: i32,
+        step: i32,
+    },
+    Toggle(bool),
+    Choice {
+        options: Vec<String>,
+        index: usize,
+    },
+    Submenu(Vec<MenuItem<T>>),
+    Back,
+}
+
+pub struct MenuItem<T> {
+    id: Option<T>,
+    label: String,
+    kind: ItemKind<T>,
+    enabled: bool,
+}
+
+impl<T: Copy + PartialEq> MenuItem<T> {
+    fn with_kind(id: Option<T>, label: &str, kind: ItemKind<T>) -> Self {
+        Self {
+            id,
+            label: String::from(label),
+            kind,
+            enabled: true,
+        }
+    }
+
+    pub fn action(id: T, label: &str) -> Self {
+        Self::with_kind(Some(id), label, ItemKind::Action)
+    }
+
+    pub fn slider(id: T, label: &str, value: i32, min: i32, max: i32, step: i32) -> Self {
+        let kind = ItemKind::Slider {
+            value,
+            min,
+            max,
+            step,
+        };
+        Self::with_kind(Some(id), label, kind)
+    }
+
+    pub fn toggle(id: T, label: &str, value: bool) -> Self {
+        Self::with_kind(Some(id), label, ItemKind::Toggle(value))
+    }
+
+    pub fn choice(id: T, label: &str, options: &[&str], index: usize) -> Self {
+        let kind = ItemKind::Choice {
+            options: options.iter().map(|option| String::from(*option)).collect(),
+            index,
+        };
+        Self::with_kind(Some(id), label, kind)
+    }
+
+    // A "Back" item is always appended as the last entry of a submenu
+    pub fn submenu(label: &str, mut items: Vec<MenuItem<T>>) -> Self {
+        items.push(Self::with_kind(None, "Back", ItemKind::Back));
+        Self::with_kind(None, label, ItemKind::Submenu(items))
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+
+    fn is_value(&self) -> bool {
+        matches!(
+            self.kind,
+            ItemKind::Slider { .. } | ItemKind::Toggle(_) | ItemKind::Choice { .. }
+        )
+    }
+
+    fn display_text(&self) -> String {
+        match &self.kind {
+            ItemKind::Slider { value, .. } => format!("{}: {}", self.label, value),
+            ItemKind::Toggle(value) => {
+                format!("{}: {}", self.label, if *value { "On" } else { "Off" })
+            }
+            ItemKind::Choice { options, index } => format!("{}: {}", self.label, options[*index]),
+            _ => self.label.clone(),
+        }
+    }
+
+    // Step a value item, returns true if the value changed
+    fn adjust(&mut self, amount: i32) -> bool {
+        match &mut self.kind {
+            ItemKind::Slider {
+                value,
+                min,
+                max,
+                step,
+            } => {
+                let new_value = (*value + amount * *step).max(*min).min(*max);
+                let changed = new_value != *value;
+                *value = new_value;
+                changed
+            }
+            ItemKind::Toggle(value) => {
+                *value = !*value;
+                true
+            }
+            ItemKind::Choice { options, index } => {
+                let count = options.len() as i32;
+                *index = ((*index as i32 + amount).rem_euclid(count)) as usize;
+                count > 1
+            }
+            _ => false,
+        }
+    }
+}
+
+pub enum MenuEvent<T> {
+    // An action item was chosen
+    Activated(T),
+    // A value item was changed
+    Changed(T),
+    // Back was requested while already at the top level
+    Back,
+    // The selection moved to another item
+    Moved,
+    // A submenu was opened or closed
+    Navigated,
+}
+
+impl<T> MenuEvent<T> {
+    pub fn feedback_sound(&self) -> Sound {
+        match self {
+            MenuEvent::Moved => Sound::MenuMove,
+            _ => Sound::MenuConfirm,
+        }
+    }
+}
+
+pub struct Menu<T> {
+    items: Vec<MenuItem<T>>,
+
+    // Indices of the opened submenus, from the top level down
+    path: Vec<usize>,
+    selected_item_index: usize,
+
+    font: graphics::Font,
+    silver_color: graphics::Color,
+    disabled_color: graphics::Color,
+
+    item_rects: Vec<graphics::Rect>,
+
+    left_indicator_paddle: Paddle,
+    right_indicator_paddle: Paddle,
+}
+
+impl<T: Copy + PartialEq> Menu<T> {
+    pub fn new(ctx: &mut Context, items: Vec<MenuItem<T>>) -> Self {
+        let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap();
+
+        let paddle_size = (6., 20.);
+        let paddle_speed = 0.;
+
+        let left_indicator_paddle = Paddle::new(
+            ctx,
+            na::Vector2::new(0., 0.),
+            graphics::Rect::new(0., 0., paddle_size.0, paddle_size.1),
+            paddle_speed,
+        );
+        let right_indicator_paddle = Paddle::new(
+            ctx,
+            na::Vector2::new(0., 0.),
+            graphics::Rect::new(0., 0., paddle_size.0, paddle_size.1),
+            paddle_speed,
+        );
+
+        let mut menu = Self {
+            items,
+            path: Vec::new(),
+            selected_item_index: 0,
+            font,
+            silver_color: graphics::Color::from_rgba(192, 192, 192, 255),
+            disabled_color: graphics::Color::from_rgba(96, 96, 96, 255),
+            item_rects: Vec::new(),
+            left_indicator_paddle,
+            right_indicator_paddle,
+        };
+        menu.select_first();
+        menu
+    }
+
+    fn current_items(&self) -> &Vec<MenuItem<T>> {
+        let mut items = &self.items;
+        for index in &self.path {
+            items = match &items[*index].kind {
+                ItemKind::Submenu(children) => children,
+                _ => unreachable!("menu path only points at submenus"),
+            };
+        }
+        items
+    }
+
+    fn current_items_mut(&mut self) -> &mut Vec<MenuItem<T>> {
+        let mut items = &mut self.items;
+        for index in &self.path {
+            items = match &mut items[*index].kind {
+                ItemKind::Submenu(children) => children,
+                _ => unreachable!("menu path only points at submenus"),
+            };
+        }
+        items
+    }
+
+    fn find_item(items: &[MenuItem<T>], id: T) -> Option<&MenuItem<T>> {
+        for item in items {
+            if item.id == Some(id) {
+                return Some(item);
+            }
+            if let ItemKind::Submenu(children) = &item.kind {
+                if let Some(found) = Self::find_item(children, id) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn set_toggle_value(&mut self, id: T, value: bool) {
+        if let Some(ItemKind::Toggle(toggle)) =
+            Self::find_item_mut(&mut self.items, id).map(|item| &mut item.kind)
+        {
+            *toggle = value;
+        }
+    }
+
+    fn find_item_mut(items: &mut [MenuItem<T>], id: T) -> Option<&mut MenuItem<T>> {
+        for item in items {
+            if item.id == Some(id) {
+                return Some(item);
+            }
+            if let ItemKind::Submenu(children) = &mut item.kind {
+                if let Some(found) = Self::find_item_mut(children, id) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn slider_value(&self, id: T) -> Option<i32> {
+        match Self::find_item(&self.items, id).map(|item| &item.kind) {
+            Some(ItemKind::Slider { value, .. }) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn toggle_value(&self, id: T) -> Option<bool> {
+        match Self::find_item(&self.items, id).map(|item| &item.kind) {
+            Some(ItemKind::Toggle(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn choice_index(&self, id: T) -> Option<usize> {
+        match Self::find_item(&self.items, id).map(|item| &item.kind) {
+            Some(ItemKind::Choice { index, .. }) => Some(*index),
+            _ => None,
+        }
+    }
+
+    fn select_first(&mut self) {
+        self.selected_item_index = self
+            .current_items()
+            .iter()
+            .position(|item| item.enabled)
+            .unwrap_or(0);
+    }
+
+    // Move the selection by one step, wrapping around and skipping disabled items
+    fn select_step(&mut self, forward: bool) -> Option<MenuEvent<T>> {
+        let item_count = self.current_items().len();
+        if item_count == 0 {
+            return None;
+        }
+
+        let mut index = self.selected_item_index;
+        for _ in 0..item_count {
+            index = if forward {
+                (index + 1) % item_count
+            } else if index == 0 {
+                item_count - 1
+            } else {
+                index - 1
+            };
+
+            if self.current_items()[index].enabled {
+                let moved = index != self.selected_item_index;
+                self.selected_item_index = index;
+                return if moved { Some(MenuEvent::Moved) } else { None };
+            }
+        }
+        None
+    }
+
+    fn back(&mut self) -> Option<MenuEvent<T>> {
+        match self.path.pop() {
+            Some(index) => {
+                self.selected_item_index = index;
+                self.item_rects.clear();
+                Some(MenuEvent::Navigated)
+            }
+            None => Some(MenuEvent::Back),
+        }
+    }
+
+    fn activate(&mut self) -> Option<MenuEvent<T>> {
+        let index = self.selected_item_index;
+        let item = self.current_items_mut().get_mut(index)?;
+        if !item.enabled {
+            return None;
+        }
+
+        match item.kind {
+            ItemKind::Action => item.id.map(MenuEvent::Activated),
+            ItemKind::Submenu(_) => {
+                self.path.push(index);
+                self.select_first();
+                // The old level's hit boxes are gone until the next draw
+                self.item_rects.clear();
+                Some(MenuEvent::Navigated)
+            }
+            ItemKind::Back => self.back(),
+            _ => {
+                if item.adjust(1) {
+                    item.id.map(MenuEvent::Changed)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn adjust(&mut self, amount: i32) -> Option<MenuEvent<T>> {
+        let index = self.selected_item_index;
+        let item = self.current_items_mut().get_mut(index)?;
+        if item.enabled && item.adjust(amount) {
+            item.id.map(MenuEvent::Changed)
+        } else {
+            None
+        }
+    }
+
+    fn selected_is_value(&self) -> bool {
+        self.current_items()
+            .get(self.selected_item_index)
+            .is_some_and(|item| item.is_value())
+    }
+
+    pub fn input(&mut self, keycode: KeyCode) -> Option<MenuEvent<T>> {
+        match keycode {
+            KeyCode::Up | KeyCode::W => self.select_step(false),
+            KeyCode::Down | KeyCode::S => self.select_step(true),
+            KeyCode::Left | KeyCode::A => self.adjust(-1),
+            KeyCode::Right | KeyCode::D if self.selected_is_value() => self.adjust(1),
+            KeyCode::Space | KeyCode::Return | KeyCode::D | KeyCode::Right => self.activate(),
+            KeyCode::Back => self.back(),
+            _ => None,
+        }
+    }
+
+    fn item_at(&self, x: f32, y: f32) -> Option<usize> {
+        self.item_rects
+            .iter()
+            .position(|rect| rect.contains(na::Point2::new(x, y)))
+            .filter(|index| {
+                self.current_items()
+                    .get(*index)
+                    .is_some_and(|item| item.enabled)
+            })
+    }
+
+    // Hovering an item selects it
+    pub fn mouse_motion(&mut self, x: f32, y: f32) -> Option<MenuEvent<T>> {
+        let index = self.item_at(x, y)?;
+        if index == self.selected_item_index {
+            return None;
+        }
+        self.selected_item_index = index;
+        Some(MenuEvent::Moved)
+    }
+
+    pub fn mouse_click(&mut self, x: f32, y: f32) -> Option<MenuEvent<T>> {
+        let index = self.item_at(x, y)?;
+        self.selected_item_index = index;
+        self.activate()
+    }
+
+    // Draw the current level with the first item's top left corner at `position`
+    pub fn draw(&mut self, ctx: &mut Context, position: na::Point2<f32>) {
+        let texts: Vec<(graphics::Text, bool)> = self
+            .current_items()
+            .iter()
+            .map(|item| {
+                let text = graphics::Text::new(
+                    graphics::TextFragment::new(item.display_text())
+                        .font(self.font)
+                        .scale(graphics::Scale::uniform(ITEM_SCALE)),
+                );
+                (text, item.enabled)
+            })
+            .collect();
+
+        self.item_rects.clear();
+
+        for (index, (mut item, enabled)) in texts.into_iter().enumerate() {
+            let width = item.width(ctx) as f32;
+            let height = item.height(ctx) as f32;
+
+            let dest = na::Point2::new(position.x, position.y + (index as f32 * ITEM_SPACING));
+            self.item_rects
+                .push(graphics::Rect::new(dest.x, dest.y, width, height));
+
+            let item_height_half = height * 0.5;
+
+            if !enabled {
+                item.fragments_mut()[0].color = Some(self.disabled_color);
+            } else if index == self.selected_item_index {
+                item.fragments_mut()[0].color = Some(graphics::WHITE);
+
+                self.left_indicator_paddle.set_position(na::Point2::new(
+                    dest.x - item_height_half,
+                    dest.y + item_height_half - (self.left_indicator_paddle.size.1 * 0.5),
+                ));
+
+                self.right_indicator_paddle.set_position(na::Point2::new(
+                    dest.x + width + item_height_half - self.right_indicator_paddle.size.0,
+                    dest.y + item_height_half - (self.right_indicator_paddle.size.1 * 0.5),
+                ));
+
+                graphics::draw(
+                    ctx,
+                    self.left_indicator_paddle.get_mesh(),
+                    graphics::DrawParam::default().dest(self.left_indicator_paddle.get_position()),
+                )
+                .unwrap();
+                graphics::draw(
+                    ctx,
+                    self.right_indicator_paddle.get_mesh(),
+                    graphics::DrawParam::default().dest(self.right_indicator_paddle.get_position()),
+                )
+                .unwrap();
+            } else {
+                item.fragments_mut()[0].color = Some(self.silver_color);
+            }
+
+            graphics::draw(ctx, &item, graphics::DrawParam::new().dest(dest)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{graphics, na, timer, Context, KeyCode};
+
+const CARET_BLINK_RATE: f64 = 2.0;
+
+pub enum TextInputEvent {
+    // Return was pressed with a non-empty text
+    Submitted(String),
+    // Backspace was pressed with an empty text
+    Cancelled,
+}
+
+pub struct TextInput {
+    text: String,
+    max_length: usize,
+    font: graphics::Font,
+}
+
+impl TextInput {
+    pub fn new(ctx: &mut Context, max_length: usize) -> Self {
+        Self {
+            text: String::new(),
+            max_length,
+            font: graphics::Font::new(ctx, "/DejaVuSerif.ttf").unwrap(),
+        }
+    }
+
+    // Start with some text already typed, e.g. a suggested name
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.chars().take(self.max_length).collect();
+        self
+    }
+
+    pub fn text_input(&mut self, character: char) {
+        if !character.is_control() && self.text.chars().count() < self.max_length {
+            self.text.push(character);
+        }
+    }
+
+    pub fn input(&mut self, keycode: KeyCode) -> Option<TextInputEvent> {
+        match keycode {
+            KeyCode::Back => {
+                if self.text.pop().is_none() {
+                    return Some(TextInputEvent::Cancelled);
+                }
+                None
+            }
+            KeyCode::Return | KeyCode::NumpadEnter => {
+                let text = self.text.trim();
+                if text.is_empty() {
+                    None
+                } else {
+                    Some(TextInputEvent::Submitted(String::from(text)))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, position: na::Point2<f32>) {
+        let blink_phase = timer::time_since_start(ctx).as_secs_f64() * CARET_BLINK_RATE;
+        let caret = if blink_phase.fract() < 0.5 { "_" } else { " " };
+
+        let text = graphics::Text::new(
+            graphics::TextFragment::new(format!("{}{}", self.text, caret))
+                .color(graphics::WHITE)
+                .font(self.font)
+                .scale(graphics::Scale::uniform(36.0)),
+        );
+        graphics::draw(ctx, &text, graphics::DrawParam::new().dest(position)).unwrap();
+    }
+}
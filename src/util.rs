@@ -12,6 +12,15 @@ impl Util {
         }
     }
 
+    // -1.0, 0.0 or 1.0 from a pair of buttons, both or neither cancel out
+    pub fn axis(negative: bool, positive: bool) -> f32 {
+        match (negative, positive) {
+            (true, false) => -1.,
+            (false, true) => 1.,
+            _ => 0.,
+        }
+    }
+
     pub fn randomize_vec(vec: &mut na::Vector2<f32>, x: f32, y: f32) {
         let mut rng = thread_rng();
         vec.x = if rng.gen_bool(0.5) { x } else { -x };
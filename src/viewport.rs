@@ -0,0 +1,65 @@
+use crate::{graphics, mouse, na, Context, GameResult};
+
+// Size of the logical arena, everything is laid out in these coordinates and
+// scaled to fit whatever window size is in use. A match on a custom arena uses
+// that arena's size instead.
+pub const ARENA_WIDTH: f32 = 800.;
+pub const ARENA_HEIGHT: f32 = 600.;
+
+pub struct Viewport {}
+
+impl Viewport {
+    // Scale an arena of `width` by `height` to fit a window of the given size,
+    // keeping its aspect ratio by letterboxing the remaining space on either side.
+    // A minimized window has no size, the coordinates are left as they are.
+    pub fn fit(
+        ctx: &mut Context,
+        window_width: f32,
+        window_height: f32,
+        width: f32,
+        height: f32,
+    ) -> GameResult {
+        if window_width <= 0. || window_height <= 0. {
+            return Ok(());
+        }
+        let scale = (window_width / width).min(window_height / height);
+        let visible_width = window_width / scale;
+        let visible_height = window_height / scale;
+
+        graphics::set_screen_coordinates(
+            ctx,
+            graphics::Rect::new(
+                -(visible_width - width) * 0.5,
+                -(visible_height - height) * 0.5,
+                visible_width,
+                visible_height,
+            ),
+        )
+    }
+
+    // Convert a position in logical window pixels, the unit mouse events come in,
+    // to arena coordinates. The window size is taken in logical pixels as well so
+    // HiDPI displays don't offset the result.
+    pub fn to_arena(ctx: &Context, x: f32, y: f32) -> na::Point2<f32> {
+        let (window_width, window_height) = graphics::window(ctx)
+            .get_inner_size()
+            .map(|size| (size.width as f32, size.height as f32))
+            .filter(|(width, height)| *width > 0. && *height > 0.)
+            .unwrap_or((ARENA_WIDTH, ARENA_HEIGHT));
+        let screen = graphics::screen_coordinates(ctx);
+
+        na::Point2::new(
+            screen.x + x / window_width * screen.w,
+            screen.y + y / window_height * screen.h,
+        )
+    }
+
+    pub fn mouse_position(ctx: &Context) -> na::Point2<f32> {
+        let position = mouse::position(ctx);
+        Self::to_arena(ctx, position.x, position.y)
+    }
+
+    pub fn arena_rect() -> graphics::Rect {
+        graphics::Rect::new(0., 0., ARENA_WIDTH, ARENA_HEIGHT)
+    }
+}
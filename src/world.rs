@@ -1,50 +1,166 @@
+use std::cmp::Ordering;
+
+use rand::{thread_rng, Rng};
+
 use crate::{
-    graphics, na,
-    player::{Player, PADDLE_WIDTH_HALF},
-    Ball, Context, Controls, KeyCode,
+    ai::Ai,
+    arena::Arena,
+    bracket::Tournament,
+    bricks::{Brick, BrickWall},
+    clock::MatchClock,
+    collidable::Collidable,
+    events::{EventListener, GameEvent},
+    graphics,
+    highscores::HighScores,
+    juice::Juice,
+    ladder::Ladder,
+    mode::{Assignment, BallLaunch, Difficulty, GameMode, FREE_FOR_ALL_LIVES},
+    na,
+    particles::ParticleSystem,
+    player::Player,
+    powerups::{PowerUpKind, PowerUps},
+    profile::{PaddleColor, Profile, ProfileStore},
+    scoring::{LastLife, LastStanding, NoLimit, PointOutcome, Scoring, ScoringKind, ScoringRule},
+    settings::Settings,
+    side::Side,
+    sound::{Sound, SoundPlayer},
+    stats::MatchStats,
+    Ball, Context, ControlMode, Controls, GamepadId, KeyCode,
 };
 
 pub const PADDING: f32 = 40.;
+// Seconds the ball waits in the middle before a serve
+const SERVE_DELAY: f32 = 0.75;
+// Ball speed multiplier once a timed match goes to sudden death
+const SUDDEN_DEATH_SPEEDUP: f32 = 1.5;
+// Thickness of the wall on the right in wall practice
+pub const WALL_THICKNESS: f32 = 20.;
+// Wall practice stops speeding the ball up here
+const PRACTICE_MAX_SPEED: f32 = 1400.;
+// Most balls in play at once in multi-ball
+const MAX_BALLS: usize = 6;
+// Vertical gap between balls served together
+const SERVE_SPACING: f32 = 60.;
+// Seconds a sticky paddle holds on to the ball
+const STICKY_HOLD: f32 = 0.75;
+// Chance a broken breakout brick leaves an item behind
+const BRICK_DROP_CHANCE: f64 = 0.25;
+// Extra balls from the multi-ball power-up
+const POWER_UP_BALLS: usize = 2;
+// Thickness of the shield wall behind a paddle
+pub const SHIELD_THICKNESS: f32 = 8.;
+// Keys of the player on each seat, in the order of `GameMode::sides`. Players on
+// the top and bottom go left with the first key and right with the second.
+const SEAT_KEYS: [(KeyCode, KeyCode); 4] = [
+    (KeyCode::W, KeyCode::S),
+    (KeyCode::Up, KeyCode::Down),
+    (KeyCode::V, KeyCode::B),
+    (KeyCode::K, KeyCode::L),
+];
+// Keys that move the mallets of player 1 and player 2 left and right in air hockey
+const MALLET_SIDE_KEYS: [(KeyCode, KeyCode); 2] =
+    [(KeyCode::A, KeyCode::D), (KeyCode::Left, KeyCode::Right)];
+
+// An edge of the arena the ball bounces off, except through the goal opening
+struct Wall {
+    side: Side,
+    // Where the face of the wall is across the edge, e.g. x for the left edge
+    face: f32,
+    // Start and end of the opening along the edge, if the edge has a goal
+    opening: Option<(f32, f32)>,
+}
+
+impl Wall {
+    fn blocks(&self, position: na::Point2<f32>) -> bool {
+        match self.opening {
+            Some((start, end)) => {
+                let along = self.side.along(position.coords);
+                along < start || along > end
+            }
+            None => true,
+        }
+    }
+}
+
+// Who plays on a seat of the match
+#[derive(Clone, Copy, PartialEq)]
+pub enum Seat {
+    // Index into the profile store
+    Profile(usize),
+    Computer(Difficulty),
+}
 
 pub struct World {
-    pub player_1: Player,
-    pub player_2: Player,
-    pub ball: Ball,
+    // Everyone in the match, in the order of `GameMode::sides`
+    pub players: Vec<Player>,
+    // Every ball in play, more than one in multi-ball
+    pub balls: Vec<Ball>,
+    // Layout of the field the match is played on
+    pub arena: Arena,
+    pub profiles: ProfileStore,
+    pub ladder: Ladder,
+    pub high_scores: HighScores,
+    // Set while a tournament is being played
+    pub tournament: Option<Tournament>,
+    pub settings: Settings,
+    sound_player: SoundPlayer,
+    pub particles: ParticleSystem,
+    pub juice: Juice,
+    pub power_ups: PowerUps,
+    pub bricks: BrickWall,
+    pub stats: MatchStats,
+    pub scoring: Scoring,
+    // Only set in timed matches
+    pub clock: Option<MatchClock>,
+    serve_timer: f32,
+    // Time since multi-ball last launched a ball
+    launch_timer: f32,
+    events: Vec<GameEvent>,
+    lineup: Vec<Seat>,
+    // Gamepads in the order they were first used, gamepad players take them in seat order
+    gamepads: Vec<GamepadId>,
+    mode: GameMode,
     max_score: i32,
     dt: f32,
 }
 
 impl World {
     pub fn new(ctx: &mut Context, max_score: i32) -> Self {
-        let (scr_width, scr_height) = graphics::drawable_size(ctx);
-        let (scr_width_half, screen_height_half) = (scr_width * 0.5, scr_height * 0.5);
-
-        // Setup player 1
-        let player_1 = Player::new(
-            ctx,
-            Controls::new(KeyCode::W, KeyCode::S),
-            na::Vector2::new(PADDLE_WIDTH_HALF + PADDING, scr_height * 0.5),
-            String::from("Player 1"),
-        );
-
-        // Setup player 2
-        let player_2 = Player::new(
-            ctx,
-            Controls::new(KeyCode::Up, KeyCode::Down),
-            na::Vector2::new(scr_width - PADDLE_WIDTH_HALF - PADDING, screen_height_half),
-            String::from("Player 2"),
-        );
+        let arena = Arena::classic();
 
+        let profiles = ProfileStore::load(ctx);
         // Setup ball
-        let ball = Ball::new(ctx, scr_width_half, screen_height_half);
+        let center = arena.center();
+        let ball = Ball::new(ctx, center.x, center.y);
 
-        Self {
-            player_1,
-            player_2,
-            ball,
+        let mut world = Self {
+            players: Vec::new(),
+            balls: vec![ball],
+            arena,
+            profiles,
+            ladder: Ladder::load(ctx),
+            high_scores: HighScores::load(ctx),
+            tournament: None,
+            settings: Settings::new(),
+            sound_player: SoundPlayer::new(),
+            particles: ParticleSystem::new(ctx),
+            juice: Juice::new(),
+            power_ups: PowerUps::new(ctx),
+            bricks: BrickWall::default(),
+            stats: MatchStats::default(),
+            scoring: Scoring::new(ScoringKind::FirstTo.rule(max_score), 1, 2),
+            clock: None,
+            serve_timer: 0.,
+            launch_timer: 0.,
+            events: Vec::new(),
+            lineup: vec![Seat::Profile(0), Seat::Profile(1)],
+            gamepads: Vec::new(),
+            mode: GameMode::Versus,
             max_score,
             dt: 0.,
-        }
+        };
+        world.reset(ctx);
+        world
     }
 
     pub fn get_dt(&self) -> f32 {
@@ -55,55 +171,677 @@ impl World {
         self.dt = new_value;
     }
 
-    pub fn check_score(&mut self, ctx: &mut Context) -> Option<&str> {
-        let scr_width = graphics::drawable_size(ctx).0;
+    pub fn get_max_score(&self) -> i32 {
+        self.max_score
+    }
+
+    pub fn set_max_score(&mut self, max_score: i32) {
+        self.max_score = max_score;
+    }
+
+    pub fn get_mode(&self) -> GameMode {
+        self.mode
+    }
+
+    // Takes effect from the next reset
+    pub fn set_mode(&mut self, mode: GameMode) {
+        self.mode = mode;
+    }
+
+    // Choose who plays on each seat from the next reset, player 1 first
+    pub fn set_lineup(&mut self, seats: &[Seat]) {
+        self.lineup = seats.to_vec();
+    }
+
+    pub fn connect_gamepad(&mut self, id: GamepadId) {
+        if !self.gamepads.contains(&id) {
+            self.gamepads.push(id);
+        }
+    }
+
+    pub fn play_sound(&self, ctx: &mut Context, sound: Sound) {
+        self.sound_player
+            .play(ctx, sound, self.settings.effective_volume());
+    }
+
+    // Player 1 has index 0 and player 2 has index 1
+    pub fn player(&self, index: usize) -> &Player {
+        &self.players[index]
+    }
+
+    // Names of the team's players, e.g. "Ann & Bob"
+    pub fn team_name(&self, team: usize) -> String {
+        let names: Vec<&str> = (0..self.players.len())
+            .filter(|index| self.players[*index].team == team)
+            .map(|index| self.players[index].get_name())
+            .collect();
+        names.join(" & ")
+    }
+
+    // Out of a free-for-all after letting in their last goal
+    pub fn is_eliminated(&self, index: usize) -> bool {
+        let team = self.players[index].team;
+        self.mode.is_free_for_all() && self.scoring.points(team) >= FREE_FOR_ALL_LIVES
+    }
+
+    pub fn is_in_play(&self, index: usize) -> bool {
+        !self.is_eliminated(index)
+    }
+
+    // The player in play defending the given edge, the back paddle in doubles
+    fn defender(&self, side: Side) -> Option<usize> {
+        (0..self.players.len())
+            .find(|index| self.players[*index].side == side && self.is_in_play(*index))
+    }
+
+    // Edges closed off by a wall, the ones the mode walls off and the ones whose
+    // players are all out of the match
+    pub fn closed_edges(&self) -> Vec<Side> {
+        Side::ALL
+            .iter()
+            .filter(|side| {
+                let is_played = self.players.iter().any(|player| player.side == **side);
+                self.mode.walls().contains(side) || (is_played && self.defender(**side).is_none())
+            })
+            .copied()
+            .collect()
+    }
+
+    // Fresh score and stats for a match with the current rules and lineup
+    pub fn begin_match(&mut self) {
+        let rule: Box<dyn ScoringRule> = match self.mode.lives() {
+            Some(lives) => Box::new(LastLife { survivor: 0, lives }),
+            None if self.mode == GameMode::Timed => Box::new(NoLimit),
+            None if self.mode.is_free_for_all() => Box::new(LastStanding {
+                lives: FREE_FOR_ALL_LIVES,
+            }),
+            None => self.settings.scoring.rule(self.max_score),
+        };
+        let best_of = match self.mode {
+            GameMode::Survival(_)
+            | GameMode::Timed
+            | GameMode::Practice(_)
+            | GameMode::FreeForAll => 1,
+            _ => self.settings.best_of,
+        };
+        self.scoring = Scoring::new(rule, best_of, self.mode.team_count());
+        self.clock = match self.mode {
+            GameMode::Timed => Some(MatchClock::new(self.settings.time_limit as f32 * 60.)),
+            _ => None,
+        };
+        let names = self
+            .players
+            .iter()
+            .map(|player| String::from(player.get_name()))
+            .collect();
+        self.stats = MatchStats::new(names);
+        self.power_ups.reset();
+        self.arena.reset();
+        self.bricks = if self.mode.has_bricks() {
+            BrickWall::new(&self.arena)
+        } else {
+            BrickWall::default()
+        };
+        // The serve brings in balls of the right kind for the mode
+        self.balls.clear();
+    }
+
+    // Put the balls back in the middle and send them off in random directions
+    pub fn serve(&mut self, ctx: &mut Context) {
+        let count = match (self.mode, self.settings.ball_launch) {
+            (GameMode::MultiBall, BallLaunch::OnPoint) => {
+                (1 + self.scoring.points_played() as usize).min(MAX_BALLS)
+            }
+            _ => 1,
+        };
+        self.balls.truncate(count);
+        while self.balls.len() < count {
+            let ball = if self.mode.is_air_hockey() {
+                Ball::puck(ctx, 0., 0.)
+            } else {
+                Ball::new(ctx, 0., 0.)
+            };
+            self.balls.push(ball);
+        }
+
+        let speedup = self.is_sudden_death() && self.settings.sudden_death_speedup;
+        let center = self.arena.center();
+        // A ball served into the brick wall starts just outside it instead
+        let clearance = if self.bricks.is_empty() {
+            0.
+        } else {
+            self.bricks.half_width() + SERVE_SPACING
+        };
+        for (index, ball) in self.balls.iter_mut().enumerate() {
+            ball.reset(center);
+            ball.position.x += clearance * ball.velocity.x.signum();
+            ball.position.y += (index as f32 - (count - 1) as f32 * 0.5) * SERVE_SPACING;
+            if speedup {
+                ball.velocity *= SUDDEN_DEATH_SPEEDUP;
+            }
+        }
+        self.serve_timer = SERVE_DELAY;
+        self.launch_timer = 0.;
+
+        // The ball heads away from the server's edge
+        let velocity = self.balls[0].velocity;
+        let away = |index: &usize| velocity.dot(&self.players[*index].side.normal());
+        let server = (0..self.players.len())
+            .filter(|index| self.is_in_play(*index))
+            .max_by(|a, b| away(a).partial_cmp(&away(b)).unwrap_or(Ordering::Equal))
+            .unwrap_or(0);
+        self.events.push(GameEvent::ServeStarted { server });
+    }
+
+    // Multi-ball sends an extra ball off from the middle
+    fn launch_ball(&mut self, ctx: &mut Context) {
+        let center = self.arena.center();
+        self.balls.push(Ball::new(ctx, center.x, center.y));
+    }
+
+    // Advance the simulation by one tick, what happened is queued as events
+    pub fn step(&mut self, ctx: &mut Context) {
+        let dt = self.dt;
+        let in_play: Vec<usize> = (0..self.players.len())
+            .filter(|index| self.is_in_play(*index))
+            .collect();
+        for index in &in_play {
+            self.players[*index].update(ctx, dt, &self.balls, &self.gamepads);
+        }
+        self.arena.update(dt);
+
+        // The ball and the clock wait between points
+        if self.serve_timer > 0. {
+            self.serve_timer -= dt;
+            return;
+        }
+
+        if let Some(clock) = &mut self.clock {
+            if clock.tick(dt) && self.time_up(ctx) {
+                return;
+            }
+        }
+
+        if let (GameMode::MultiBall, BallLaunch::Every(seconds)) =
+            (self.mode, self.settings.ball_launch)
+        {
+            self.launch_timer += dt;
+            if self.launch_timer >= seconds as f32 {
+                self.launch_timer = 0.;
+                if self.balls.len() < MAX_BALLS {
+                    self.launch_ball(ctx);
+                }
+            }
+        }
+
+        self.update_power_ups(ctx, dt);
+
+        let acceleration = self.mode.ball_acceleration();
+        let is_puck = self.mode.is_air_hockey();
+        let ball_dt = dt * self.power_ups.ball_speed_factor();
+        let walls = self.walls();
+        let (arena_width, arena_height) = (self.arena.width, self.arena.height);
+        let mut broken = Vec::new();
+        for ball in &mut self.balls {
+            ball.age(dt);
+
+            // A ball caught by a sticky paddle goes where the paddle goes
+            if let Some(hold) = &ball.hold {
+                let paddle = &self.players[hold.player].paddle;
+                if ball.follow(paddle.get_position(), dt) {
+                    continue;
+                }
+            }
+
+            if acceleration > 0. {
+                ball.accelerate(acceleration * dt, PRACTICE_MAX_SPEED);
+            }
+            if is_puck {
+                ball.slide(dt);
+            }
+
+            ball.update(ball_dt);
+
+            // Walls stand where there's no goal, a ball already through one carries on
+            let mut min = na::Point2::new(f32::NEG_INFINITY, f32::NEG_INFINITY);
+            let mut max = na::Point2::new(f32::INFINITY, f32::INFINITY);
+            for wall in &walls {
+                if wall.side.is_past(ball.position, arena_width, arena_height)
+                    || !wall.blocks(ball.position)
+                {
+                    continue;
+                }
+                match wall.side {
+                    Side::Left => min.x = wall.face,
+                    Side::Top => min.y = wall.face,
+                    Side::Right => max.x = wall.face,
+                    Side::Bottom => max.y = wall.face,
+                }
+            }
+            let mut wall_hit = ball.bounce_off_walls(min, max);
+            for obstacle in &self.arena.obstacles {
+                wall_hit |= ball.bounce_off(obstacle);
+            }
+            if let Some(brick) = self.bricks.hit(ball) {
+                if brick.hit_points <= 0 {
+                    broken.push((brick, ball.last_hit));
+                } else {
+                    wall_hit = true;
+                }
+            }
+            if wall_hit {
+                self.events.push(GameEvent::BallHitWall {
+                    position: ball.position,
+                    speed: ball.velocity.norm(),
+                });
+            }
+
+            // Collision check, only a ball heading towards the paddle bounces off a flat
+            // one and a mallet knocks the ball away with its own speed
+            for index in &in_play {
+                let paddle = &self.players[*index].paddle;
+                let is_hit = match paddle.mallet_radius() {
+                    Some(radius) => ball.strike(paddle.get_position(), radius, paddle.velocity),
+                    None => {
+                        paddle.check_collision(ball)
+                            && ball.deflect(self.players[*index].side.normal())
+                    }
+                };
+                if !is_hit {
+                    continue;
+                }
+                let player = &self.players[*index];
+
+                ball.last_hit = Some(*index);
+                if self.power_ups.is_active(PowerUpKind::Sticky, *index) {
+                    ball.catch(*index, player.paddle.get_position(), STICKY_HOLD);
+                }
+                let offset = player.paddle.hit_offset(ball);
+                self.events.push(GameEvent::BallHitPaddle {
+                    player: *index,
+                    offset,
+                    position: ball.position,
+                    speed: ball.velocity.norm(),
+                });
+            }
+        }
+
+        for (brick, player) in broken {
+            self.brick_broken(&brick, player);
+        }
+
+        // Extra balls from the multi-ball power-up vanish when their time is up
+        self.balls.retain(|ball| !ball.is_expired());
+
+        self.collide_balls();
+        self.check_score(ctx);
+    }
+
+    // The four edges of the arena. An edge with a player in play has a goal and
+    // edges no one plays on are plain walls. Closed edges have a thick wall and a
+    // shield power-up puts one in front of the goal.
+    fn walls(&self) -> Vec<Wall> {
+        let (width, height) = (self.arena.width, self.arena.height);
+        let closed = self.closed_edges();
+        Side::ALL
+            .iter()
+            .map(|side| {
+                let edge = side.edge(width, height);
+                // +1 or -1, whichever way is into the arena
+                let inward = side.depth(side.normal());
+                let defender = self.defender(*side);
+                let shielded = (0..self.players.len()).any(|player| {
+                    self.players[player].side == *side
+                        && self.is_in_play(player)
+                        && self.power_ups.is_active(PowerUpKind::Shield, player)
+                });
+
+                let (face, opening) = if shielded {
+                    (edge + inward * SHIELD_THICKNESS, None)
+                } else if defender.is_some() {
+                    (edge, Some(self.arena.goal_range(*side)))
+                } else if closed.contains(side) {
+                    (edge + inward * WALL_THICKNESS, None)
+                } else {
+                    (edge, None)
+                };
+                Wall {
+                    side: *side,
+                    face,
+                    opening,
+                }
+            })
+            .collect()
+    }
 
-        // Check which side scored
-        if self.ball.position.x < 0.0 {
-            self.player_2.increment_score();
-            self.ball.reset(ctx);
+    // Spawn and collect items, then apply what's active to the paddles
+    fn update_power_ups(&mut self, ctx: &mut Context, dt: f32) {
+        if !self.mode.has_power_ups() {
+            return;
         }
-        if self.ball.position.x > scr_width {
-            self.player_1.increment_score();
-            self.ball.reset(ctx);
+        // Breakout items only come out of the bricks
+        let spawns = !self.mode.has_bricks();
+        self.power_ups
+            .update(&self.settings, &self.arena, dt, spawns);
+
+        // Items go to whoever last hit the ball, a ball nobody has touched passes by
+        let mut collected = Vec::new();
+        for ball in &self.balls {
+            if let Some(player) = ball.last_hit {
+                if let Some(item) = self.power_ups.take(ball) {
+                    collected.push((item, player));
+                }
+            }
         }
 
-        if self.player_1.get_score() >= self.max_score {
-            self.reset(ctx);
-            return Some(self.player_1.get_name());
+        for (item, player) in collected {
+            let team = self.players[player].team;
+            let opponents: Vec<usize> = (0..self.players.len())
+                .filter(|other| self.players[*other].team != team)
+                .collect();
+            self.power_ups.activate(item.kind, player, &opponents);
+            self.events.push(GameEvent::PowerUpCollected {
+                kind: item.kind,
+                player,
+                position: item.position,
+            });
+
+            if item.kind == PowerUpKind::MultiBall {
+                for _ in 0..POWER_UP_BALLS {
+                    let ball = Ball::new(ctx, item.position.x, item.position.y)
+                        .with_lifetime(item.kind.duration());
+                    self.balls.push(ball);
+                }
+            }
         }
 
-        if self.player_2.get_score() >= self.max_score {
-            self.reset(ctx);
-            return Some(self.player_2.get_name());
+        for (index, player) in self.players.iter_mut().enumerate() {
+            player
+                .paddle
+                .set_length_scale(self.power_ups.paddle_scale(index));
+            player.reversed = self.power_ups.is_active(PowerUpKind::Reverse, index);
         }
+    }
 
-        None
+    // Every pair of balls bounces off each other
+    fn collide_balls(&mut self) {
+        for index in 0..self.balls.len() {
+            let (head, tail) = self.balls.split_at_mut(index + 1);
+            let ball = &mut head[index];
+            for other in tail {
+                if ball.collide(other) {
+                    self.events.push(GameEvent::BallHitBall {
+                        position: na::center(&ball.position, &other.position),
+                        speed: ball.velocity.norm().max(other.velocity.norm()),
+                    });
+                }
+            }
+        }
     }
 
-    // Reset both player 1 and player 2 to origin position and 0 score
-    // FIXME: - remove duplicate code for init players
-    pub fn reset(&mut self, ctx: &mut Context) {
-        let (scr_width, scr_height) = graphics::drawable_size(ctx);
-        let screen_height_half = scr_height * 0.5;
-
-        // Setup player 1
-        let player_1 = Player::new(
-            ctx,
-            Controls::new(KeyCode::W, KeyCode::S),
-            na::Vector2::new(PADDLE_WIDTH_HALF + PADDING, scr_height * 0.5),
-            String::from("Player 1"),
-        );
+    // Hand this tick's events to every subsystem, they are returned for the caller
+    pub fn dispatch_events(&mut self, ctx: &mut Context) -> Vec<GameEvent> {
+        let events: Vec<GameEvent> = self.events.drain(..).collect();
+        for event in &events {
+            self.sound_player.on_event(ctx, &self.settings, event);
+            self.particles.on_event(ctx, &self.settings, event);
+            self.juice.on_event(ctx, &self.settings, event);
+            self.stats.on_event(ctx, &self.settings, event);
+        }
+        events
+    }
+
+    // Every ball that leaves the arena scores, the point is over once all balls are gone
+    fn check_score(&mut self, ctx: &mut Context) {
+        let (width, height) = (self.arena.width, self.arena.height);
+
+        let mut index = 0;
+        while index < self.balls.len() {
+            // Check which edge the ball left through
+            let ball = &self.balls[index];
+            let (position, last_hit) = (ball.position, ball.last_hit);
+            let side = match Side::ALL
+                .iter()
+                .find(|side| side.is_past(position, width, height))
+            {
+                Some(side) => *side,
+                None => {
+                    index += 1;
+                    continue;
+                }
+            };
+            self.balls.remove(index);
+
+            let conceder = match self.defender(side) {
+                Some(conceder) => conceder,
+                None => continue,
+            };
+            // Either the conceding team gets the point against them, or the team that
+            // last hit the ball scores, an own goal goes to the first other team.
+            // The point is credited to whoever of the scoring team hit the ball last.
+            let conceding = self.players[conceder].team;
+            let team_of = |player: &usize| self.players[*player].team;
+            let (scorer, counted) = if self.mode.counts_goals_against() {
+                (
+                    last_hit.filter(|player| team_of(player) != conceding),
+                    conceding,
+                )
+            } else {
+                let team = last_hit
+                    .map(|player| team_of(&player))
+                    .filter(|team| *team != conceding)
+                    .or_else(|| (0..self.mode.team_count()).find(|team| *team != conceding))
+                    .unwrap_or(conceding);
+                let scorer = last_hit
+                    .filter(|player| team_of(player) == team)
+                    .or_else(|| (0..self.players.len()).find(|player| team_of(player) == team));
+                (scorer, team)
+            };
+            self.events.push(GameEvent::PointScored {
+                scorer,
+                conceder,
+                position,
+            });
+
+            let outcome = self.scoring.point_scored(counted);
+
+            // In sudden death the next point wins
+            if self.is_sudden_death() {
+                self.finish_match(ctx, counted);
+                return;
+            }
+
+            match outcome {
+                PointOutcome::Continue => {}
+                // A new set starts with a fresh serve and a fresh brick wall
+                PointOutcome::SetWon(winner) => {
+                    self.events.push(GameEvent::SetWon { winner });
+                    self.balls.clear();
+                    if self.mode.has_bricks() {
+                        self.bricks = BrickWall::new(&self.arena);
+                    }
+                }
+                PointOutcome::MatchWon(winner) => {
+                    self.finish_match(ctx, winner);
+                    return;
+                }
+            }
+        }
+
+        if self.balls.is_empty() {
+            self.serve(ctx);
+        }
+    }
 
-        // Setup player 2
-        let player_2 = Player::new(
-            ctx,
-            Controls::new(KeyCode::Up, KeyCode::Down),
-            na::Vector2::new(scr_width - PADDLE_WIDTH_HALF - PADDING, screen_height_half),
-            String::from("Player 2"),
+    // A broken brick adds its points to the bonus tally of whoever hit the ball
+    // last and sometimes leaves an item behind. Only goals decide the match.
+    fn brick_broken(&mut self, brick: &Brick, player: Option<usize>) {
+        self.events.push(GameEvent::BrickBroken {
+            player,
+            points: brick.max_hit_points,
+            position: brick.position,
+        });
+        if thread_rng().gen_bool(BRICK_DROP_CHANCE) {
+            self.power_ups.drop_at(&self.settings, brick.position);
+        }
+    }
+
+    // Bonus points from the bricks the team broke
+    pub fn brick_points(&self, team: usize) -> u32 {
+        self.players
+            .iter()
+            .zip(&self.stats.brick_points)
+            .filter(|(player, _)| player.team == team)
+            .map(|(_, points)| *points)
+            .sum()
+    }
+
+    pub fn is_sudden_death(&self) -> bool {
+        self.clock.as_ref().is_some_and(|clock| clock.sudden_death)
+    }
+
+    // The leader wins when time runs out, a tie goes to sudden death.
+    // Returns true if the match is over.
+    fn time_up(&mut self, ctx: &mut Context) -> bool {
+        let score = self.scoring.match_score();
+        if score[0] != score[1] {
+            let winner = if score[0] > score[1] { 0 } else { 1 };
+            self.finish_match(ctx, winner);
+            return true;
+        }
+
+        if let Some(clock) = &mut self.clock {
+            clock.sudden_death = true;
+        }
+        if self.settings.sudden_death_speedup {
+            for ball in &mut self.balls {
+                ball.velocity *= SUDDEN_DEATH_SPEEDUP;
+            }
+        }
+        false
+    }
+
+    fn finish_match(&mut self, ctx: &mut Context, winner: usize) {
+        self.events.push(GameEvent::MatchWon { winner });
+        self.record_result(ctx, winner);
+        self.reset(ctx);
+    }
+
+    // Update the ladder with the finished match between the two profiles
+    fn record_result(&mut self, ctx: &mut Context, winner: usize) {
+        // The ladder only rates one on one matches between people
+        if self.mode.is_solo() || self.players.len() != 2 {
+            return;
+        }
+
+        let loser = 1 - winner;
+        let score = self.scoring.match_score();
+        let (winner_name, loser_name) = (
+            String::from(self.player(winner).get_name()),
+            String::from(self.player(loser).get_name()),
         );
+        self.ladder
+            .record(&winner_name, &loser_name, score[winner], score[loser]);
+        self.ladder.save(ctx);
+    }
+
+    // Seat a fresh player on every spot the mode assigns
+    pub fn reset(&mut self, ctx: &mut Context) {
+        if let Some(height) = self.mode.goal_height() {
+            self.arena.add_default_goals(height);
+        }
+        self.players = self
+            .mode
+            .assignments()
+            .into_iter()
+            .enumerate()
+            .map(|(index, assignment)| self.create_player(ctx, index, assignment))
+            .collect();
+    }
+
+    fn create_player(&self, ctx: &mut Context, index: usize, assignment: Assignment) -> Player {
+        let (up_key, down_key) = SEAT_KEYS[index];
+        let side = assignment.side;
+        let start = self.arena.paddle_start(side).coords + side.normal() * assignment.depth;
+        let track_length = side.length(self.arena.width, self.arena.height);
+
+        let mut controls = Controls::new(up_key, down_key);
+        if let (true, Some((left_key, right_key))) =
+            (self.mode.is_air_hockey(), MALLET_SIDE_KEYS.get(index))
+        {
+            controls = controls.with_side_keys(*left_key, *right_key);
+        }
+
+        let seat = match (index, self.mode.computer_opponent()) {
+            // In solo modes the computer takes the place of player 2
+            (1, Some(difficulty)) => Seat::Computer(difficulty),
+            _ => self
+                .lineup
+                .get(index)
+                .copied()
+                .unwrap_or(Seat::Profile(index)),
+        };
+
+        let mut player = match seat {
+            Seat::Computer(difficulty) => {
+                let name = if self.mode.has_computer_seats() {
+                    format!("Computer {}", index + 1)
+                } else {
+                    format!("Computer ({})", difficulty.name())
+                };
+                let mut player = Player::new(
+                    ctx,
+                    controls,
+                    assignment,
+                    start,
+                    name,
+                    PaddleColor::White.to_color(),
+                );
+                player.set_ai(Ai::new(difficulty, track_length));
+                player
+            }
+            Seat::Profile(profile) => {
+                let profile = self.seat_profile(index, profile);
+                // Gamepad players get the pads in seat order
+                let gamepad = (0..index)
+                    .filter(|earlier| match self.lineup.get(*earlier) {
+                        Some(Seat::Profile(other)) => {
+                            self.seat_profile(*earlier, *other).controls == ControlMode::Gamepad
+                        }
+                        _ => false,
+                    })
+                    .count();
+                Player::new(
+                    ctx,
+                    controls.with_mode(profile.controls).with_gamepad(gamepad),
+                    assignment,
+                    start,
+                    profile.name,
+                    profile.color.to_color(),
+                )
+            }
+        };
+
+        player.paddle.set_track_length(track_length);
+
+        // Air hockey mallets go anywhere in their own half
+        if self.mode.is_air_hockey() {
+            let (width, height) = (self.arena.width, self.arena.height);
+            let left = if side == Side::Left { 0. } else { width * 0.5 };
+            player.use_mallet(ctx, graphics::Rect::new(left, 0., width * 0.5, height));
+        }
+        player
+    }
 
-        self.player_1 = player_1;
-        self.player_2 = player_2;
+    // A seat without a stored profile gets a stand-in named after the seat
+    fn seat_profile(&self, index: usize, profile: usize) -> Profile {
+        self.profiles
+            .profiles
+            .get(profile)
+            .cloned()
+            .unwrap_or_else(|| {
+                let color = PaddleColor::ALL[index % PaddleColor::ALL.len()];
+                Profile::new(&format!("Player {}", index + 1), color)
+            })
     }
 }